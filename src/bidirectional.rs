@@ -0,0 +1,326 @@
+use std::cmp::Ordering;
+
+use crate::error::{OverflowError, SearchExhausted};
+use crate::infinite_set::take_merged;
+
+/// The BidirectionalSet trait, a companion to InfiniteSet for ordered sets that go on forever in
+/// both directions, such as the set of all integers.
+///
+/// An InfiniteSet needs a least element to start iterating from, so instead a BidirectionalSet is
+/// split at an anchor of zero: next_up() walks the elements at or above zero in ascending order,
+/// and next_down() walks the elements below zero in descending order. Either direction may run
+/// out (the negative odd numbers have nothing at or above zero), in which case it returns None.
+pub trait BidirectionalSet {
+    type Item;
+
+    /// A function to determine if `x` could exist in the set, in either direction.
+    fn contains(&self, x: &Self::Item) -> bool;
+
+    /// Returns the next element at or above zero, in ascending order.
+    fn next_up(&mut self) -> Option<Self::Item>;
+
+    /// Returns the next element below zero, in descending order.
+    fn next_down(&mut self) -> Option<Self::Item>;
+
+    /// Returns the OverflowError if either direction ended because its next element would not fit
+    /// in the element type, or None if neither has. See `InfiniteSet::overflow`.
+    fn overflow(&self) -> Option<OverflowError> {
+        None
+    }
+
+    /// Returns a BidirectionalUnion between this set and another.
+    fn union<I>(self, other: I) -> BidirectionalUnion<Self::Item>
+    where
+        Self::Item: Ord,
+        Self: Sized + 'static,
+        I: BidirectionalSet<Item = Self::Item> + 'static,
+    {
+        BidirectionalUnion::from_sets(self, other)
+    }
+
+    /// Returns a BidirectionalIntersection between this set and another.
+    fn intersect<I>(self, other: I) -> BidirectionalIntersection<Self::Item>
+    where
+        Self::Item: Ord,
+        Self: Sized + 'static,
+        I: BidirectionalSet<Item = Self::Item> + 'static,
+    {
+        BidirectionalIntersection::from_sets(self, other)
+    }
+
+    /// Returns an iterator that alternates between both directions (0, -1, 1, -2, 2, ... for the
+    /// integers), so that every element is reached after finitely many steps.
+    fn interleaved(self) -> Interleaved<Self>
+    where
+        Self: Sized,
+    {
+        Interleaved {
+            set: self,
+            down_next: false,
+        }
+    }
+}
+
+/// An iterator over both directions of a BidirectionalSet, taking turns between them. Once one
+/// direction runs out, the other is used for the rest of the iteration.
+pub struct Interleaved<S> {
+    set: S,
+
+    /// Whether the next turn belongs to next_down().
+    down_next: bool,
+}
+
+impl<S: BidirectionalSet> Iterator for Interleaved<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let down_turn = self.down_next;
+        self.down_next = !self.down_next;
+
+        // if this turn's direction has run out, the other direction takes the turn instead
+        if down_turn {
+            self.set.next_down().or_else(|| self.set.next_up())
+        } else {
+            self.set.next_up().or_else(|| self.set.next_down())
+        }
+    }
+}
+
+/// A union between two bidirectional sets. BidirectionalUnion is also a BidirectionalSet.
+///
+/// Each direction is merged separately the same way InfiniteUnion merges two ascending sets,
+/// except that the downward direction is merged in descending order.
+pub struct BidirectionalUnion<T> {
+    first: Box<dyn BidirectionalSet<Item = T>>,
+    second: Box<dyn BidirectionalSet<Item = T>>,
+
+    /// The next upward values of the first and second sets, once the upward merge has started.
+    up_next: Option<(Option<T>, Option<T>)>,
+
+    /// The next downward values of the first and second sets, once the downward merge has
+    /// started.
+    down_next: Option<(Option<T>, Option<T>)>,
+}
+
+impl<T: Ord> BidirectionalUnion<T> {
+    pub fn from_sets<I, J>(first: I, second: J) -> Self
+    where
+        I: BidirectionalSet<Item = T> + 'static,
+        J: BidirectionalSet<Item = T> + 'static,
+    {
+        Self {
+            first: Box::new(first),
+            second: Box::new(second),
+            up_next: None,
+            down_next: None,
+        }
+    }
+}
+
+impl<T: Ord> BidirectionalSet for BidirectionalUnion<T> {
+    type Item = T;
+
+    fn contains(&self, x: &T) -> bool {
+        self.first.contains(x) || self.second.contains(x)
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first.overflow().or_else(|| self.second.overflow())
+    }
+
+    fn next_up(&mut self) -> Option<T> {
+        if self.up_next.is_none() {
+            self.up_next = Some((self.first.next_up(), self.second.next_up()));
+        }
+
+        let first = &mut self.first;
+        let second = &mut self.second;
+        let (first_next, second_next) = self.up_next.as_mut()?;
+
+        take_merged(
+            first_next,
+            second_next,
+            || first.next_up(),
+            || second.next_up(),
+            Ordering::Less,
+        )
+    }
+
+    fn next_down(&mut self) -> Option<T> {
+        if self.down_next.is_none() {
+            self.down_next = Some((self.first.next_down(), self.second.next_down()));
+        }
+
+        let first = &mut self.first;
+        let second = &mut self.second;
+        let (first_next, second_next) = self.down_next.as_mut()?;
+
+        take_merged(
+            first_next,
+            second_next,
+            || first.next_down(),
+            || second.next_down(),
+            Ordering::Greater,
+        )
+    }
+}
+
+/// An intersection between two bidirectional sets. BidirectionalIntersection is also a
+/// BidirectionalSet.
+///
+/// Each direction is iterated by merging both sets in that direction, the same way
+/// BidirectionalUnion does, and always advancing the set that is behind until the two agree on a
+/// value. A direction ends as soon as either set runs out in it, so an intersection with a set
+/// that has nothing at or above zero (such as the negative odd numbers) has nothing upward either.
+///
+/// WARNING: like InfiniteIntersection, calling next_up() or next_down() on a direction that has
+/// no more shared elements will stall the program, unless a search budget has been set with
+/// `with_budget`. Use `try_next_up_within` and `try_next_down_within` to search a bounded number
+/// of candidates instead.
+pub struct BidirectionalIntersection<T> {
+    first: Box<dyn BidirectionalSet<Item = T>>,
+    second: Box<dyn BidirectionalSet<Item = T>>,
+
+    /// The next upward values of the first and second sets, once the upward search has started.
+    up_next: Option<(Option<T>, Option<T>)>,
+
+    /// The next downward values of the first and second sets, once the downward search has
+    /// started.
+    down_next: Option<(Option<T>, Option<T>)>,
+
+    /// The most candidates next_up() and next_down() will check before giving up, if any.
+    budget: Option<usize>,
+}
+
+impl<T: Ord> BidirectionalIntersection<T> {
+    pub fn from_sets<I, J>(first: I, second: J) -> Self
+    where
+        I: BidirectionalSet<Item = T> + 'static,
+        J: BidirectionalSet<Item = T> + 'static,
+    {
+        Self {
+            first: Box::new(first),
+            second: Box::new(second),
+            up_next: None,
+            down_next: None,
+            budget: None,
+        }
+    }
+
+    /// Limits every call to next_up() and next_down() to checking at most `limit` candidates.
+    /// If no element is found within the budget, they return None instead of searching forever.
+    pub fn with_budget(mut self, limit: usize) -> Self {
+        self.budget = Some(limit);
+        self
+    }
+
+    /// Finds the next shared element at or above zero, advancing the sets at most `limit` times.
+    /// Rejected candidates are consumed, so calling this again resumes the search where it left
+    /// off.
+    ///
+    /// Returns Ok(None) if either set has run out of elements at or above zero.
+    pub fn try_next_up_within(&mut self, limit: usize) -> Result<Option<T>, SearchExhausted> {
+        if self.up_next.is_none() {
+            self.up_next = Some((self.first.next_up(), self.second.next_up()));
+        }
+
+        let first = &mut self.first;
+        let second = &mut self.second;
+        let (first_next, second_next) = self.up_next.as_mut().expect("the search has started");
+
+        take_shared(
+            first_next,
+            second_next,
+            || first.next_up(),
+            || second.next_up(),
+            Ordering::Less,
+            limit,
+        )
+    }
+
+    /// Finds the next shared element below zero, advancing the sets at most `limit` times. See
+    /// `try_next_up_within`.
+    pub fn try_next_down_within(&mut self, limit: usize) -> Result<Option<T>, SearchExhausted> {
+        if self.down_next.is_none() {
+            self.down_next = Some((self.first.next_down(), self.second.next_down()));
+        }
+
+        let first = &mut self.first;
+        let second = &mut self.second;
+        let (first_next, second_next) = self.down_next.as_mut().expect("the search has started");
+
+        take_shared(
+            first_next,
+            second_next,
+            || first.next_down(),
+            || second.next_down(),
+            Ordering::Greater,
+            limit,
+        )
+    }
+}
+
+/// Advances whichever of two merged sets is behind until they agree on a value, and takes it.
+/// `behind` is the ordering of a value that comes before another in the direction of the merge.
+/// Gives up after `limit` advances, and returns Ok(None) once either set has ended.
+fn take_shared<T: Ord>(
+    first_next: &mut Option<T>,
+    second_next: &mut Option<T>,
+    mut advance_first: impl FnMut() -> Option<T>,
+    mut advance_second: impl FnMut() -> Option<T>,
+    behind: Ordering,
+    limit: usize,
+) -> Result<Option<T>, SearchExhausted> {
+    for _ in 0..limit {
+        let ordering = match (first_next.as_ref(), second_next.as_ref()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            // nothing else can be shared with a set that has ended
+            _ => return Ok(None),
+        };
+
+        if ordering == Ordering::Equal {
+            *second_next = advance_second();
+            return Ok(std::mem::replace(first_next, advance_first()));
+        } else if ordering == behind {
+            *first_next = advance_first();
+        } else {
+            *second_next = advance_second();
+        }
+    }
+
+    Err(SearchExhausted { candidates: limit })
+}
+
+impl<T: Ord> BidirectionalSet for BidirectionalIntersection<T> {
+    type Item = T;
+
+    fn contains(&self, x: &T) -> bool {
+        self.first.contains(x) && self.second.contains(x)
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first.overflow().or_else(|| self.second.overflow())
+    }
+
+    fn next_up(&mut self) -> Option<T> {
+        match self.budget {
+            Some(limit) => self.try_next_up_within(limit).ok().flatten(),
+            None => loop {
+                if let Ok(next) = self.try_next_up_within(usize::MAX) {
+                    break next;
+                }
+            },
+        }
+    }
+
+    fn next_down(&mut self) -> Option<T> {
+        match self.budget {
+            Some(limit) => self.try_next_down_within(limit).ok().flatten(),
+            None => loop {
+                if let Ok(next) = self.try_next_down_within(usize::MAX) {
+                    break next;
+                }
+            },
+        }
+    }
+}
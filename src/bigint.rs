@@ -0,0 +1,507 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+use num_integer::Integer;
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, Num, One, ToPrimitive, Zero};
+
+/// An arbitrary-precision unsigned integer, for sets that grow too fast for any primitive type.
+///
+/// BigUint implements everything that Element requires, so every built-in set can be made of it
+/// (for example `InfiniteTwoPowers::<BigUint>::new()`) and will keep going for as long as there
+/// is memory to hold its elements. It only supports the arithmetic the sets need, and favors
+/// simplicity over speed.
+///
+/// Like the primitive unsigned types, subtracting a larger number from a smaller one panics.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first. There are never any trailing zero digits, so
+    /// zero is the empty vector and every number has exactly one representation.
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    fn from_digits(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Self { digits }
+    }
+
+    /// Returns the number of bits needed to represent this number, which is zero for zero.
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            Some(top) => self.digits.len() as u64 * 32 - u64::from(top.leading_zeros()),
+            None => 0,
+        }
+    }
+
+    /// Divides by a single digit, returning the quotient and remainder.
+    fn div_rem_digit(&self, divisor: u32) -> (Self, u32) {
+        let divisor = u64::from(divisor);
+        let mut quotient = vec![0; self.digits.len()];
+        let mut remainder = 0u64;
+
+        for (i, digit) in self.digits.iter().enumerate().rev() {
+            let current = remainder << 32 | u64::from(*digit);
+            quotient[i] = (current / divisor) as u32;
+            remainder = current % divisor;
+        }
+
+        (Self::from_digits(quotient), remainder as u32)
+    }
+
+    /// Long division a digit at a time, with Knuth's algorithm D (The Art of Computer
+    /// Programming, vol. 2, 4.3.1). Each digit of the quotient is estimated from the top digits
+    /// of the running remainder and the divisor, which is off by at most 2 once the divisor is
+    /// shifted so that its top bit is set.
+    fn div_rem_big(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+        if let [digit] = divisor.digits[..] {
+            let (quotient, remainder) = self.div_rem_digit(digit);
+            return (quotient, Self::from(u64::from(remainder)));
+        }
+
+        const BASE: u128 = 1 << 32;
+        let shift = divisor
+            .digits
+            .last()
+            .expect("the divisor isn't zero")
+            .leading_zeros();
+        let v = shifted_left(&divisor.digits, shift);
+        let mut u = shifted_left(&self.digits, shift);
+        let n = v.len();
+
+        // the shift can carry into a new top digit, and algorithm D needs room for one either way
+        if u.len() == self.digits.len() {
+            u.push(0);
+        }
+
+        let mut quotient = vec![0u32; u.len() - n];
+        for j in (0..quotient.len()).rev() {
+            // estimate the quotient digit from the top two digits of the remainder, and correct
+            // it with the third, which leaves it at most 1 too large
+            let top = u128::from(u[j + n]) << 32 | u128::from(u[j + n - 1]);
+            let mut estimate = top / u128::from(v[n - 1]);
+            let mut rest = top % u128::from(v[n - 1]);
+            while estimate >= BASE
+                || estimate * u128::from(v[n - 2]) > (rest << 32 | u128::from(u[j + n - 2]))
+            {
+                estimate -= 1;
+                rest += u128::from(v[n - 1]);
+                if rest >= BASE {
+                    break;
+                }
+            }
+
+            // subtract estimate * v from the remainder, digit by digit
+            let estimate = estimate as u64;
+            let mut borrow = 0i64;
+            for i in 0..n {
+                let product = estimate * u64::from(v[i]);
+                let difference = i64::from(u[i + j]) - borrow - (product & 0xffff_ffff) as i64;
+                u[i + j] = difference as u32;
+                borrow = (product >> 32) as i64 - (difference >> 32);
+            }
+            let difference = i64::from(u[j + n]) - borrow;
+            u[j + n] = difference as u32;
+
+            // the estimate was 1 too large, so add v back
+            if difference < 0 {
+                quotient[j] = (estimate - 1) as u32;
+                let mut carry = 0u64;
+                for i in 0..n {
+                    let sum = u64::from(u[i + j]) + u64::from(v[i]) + carry;
+                    u[i + j] = sum as u32;
+                    carry = sum >> 32;
+                }
+                u[j + n] = u[j + n].wrapping_add(carry as u32);
+            } else {
+                quotient[j] = estimate as u32;
+            }
+        }
+
+        // what is left of u is the remainder, still shifted
+        u.truncate(n);
+        let remainder = shifted_right(&u, shift);
+
+        (Self::from_digits(quotient), Self::from_digits(remainder))
+    }
+}
+
+/// Shifts digits left by fewer than 32 bits, adding a digit for whatever is carried out of the
+/// top.
+fn shifted_left(digits: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return digits.to_vec();
+    }
+
+    let mut shifted = Vec::with_capacity(digits.len() + 1);
+    let mut carry = 0;
+    for digit in digits {
+        shifted.push(digit << shift | carry);
+        carry = digit >> (32 - shift);
+    }
+    if carry != 0 {
+        shifted.push(carry);
+    }
+
+    shifted
+}
+
+/// Shifts digits right by fewer than 32 bits.
+fn shifted_right(digits: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return digits.to_vec();
+    }
+
+    (0..digits.len())
+        .map(|i| {
+            let above = digits.get(i + 1).map_or(0, |digit| digit << (32 - shift));
+            digits[i] >> shift | above
+        })
+        .collect()
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        Self::from(u128::from(n))
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(mut n: u128) -> Self {
+        let mut digits = Vec::new();
+        while n != 0 {
+            digits.push(n as u32);
+            n >>= 32;
+        }
+
+        Self { digits }
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // with no trailing zeros, the number with more digits is always larger
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return f.pad_integral(true, "", "0");
+        }
+
+        // peel off nine decimal digits at a time, least significant first
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_digit(1_000_000_000);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        let mut decimal = chunks.pop().map(|top| top.to_string()).unwrap_or_default();
+        for chunk in chunks.iter().rev() {
+            decimal.push_str(&format!("{:09}", chunk));
+        }
+
+        f.pad_integral(true, "", &decimal)
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<'a> Add<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.digits.len() >= other.digits.len() {
+            (&self.digits, &other.digits)
+        } else {
+            (&other.digits, &self.digits)
+        };
+
+        let mut digits = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0u64;
+        for (i, digit) in long.iter().enumerate() {
+            let sum = u64::from(*digit) + u64::from(*short.get(i).unwrap_or(&0)) + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+
+        BigUint::from_digits(digits)
+    }
+}
+
+impl<'a> Sub<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(*self >= *other, "attempt to subtract with overflow");
+
+        let mut digits = Vec::with_capacity(self.digits.len());
+        let mut borrow = 0i64;
+        for (i, digit) in self.digits.iter().enumerate() {
+            let mut difference =
+                i64::from(*digit) - i64::from(*other.digits.get(i).unwrap_or(&0)) - borrow;
+            borrow = 0;
+            if difference < 0 {
+                difference += 1 << 32;
+                borrow = 1;
+            }
+            digits.push(difference as u32);
+        }
+
+        BigUint::from_digits(digits)
+    }
+}
+
+impl<'a> Mul<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, a) in self.digits.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.digits.iter().enumerate() {
+                let product = u64::from(*a) * u64::from(*b) + u64::from(digits[i + j]) + carry;
+                digits[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+
+        BigUint::from_digits(digits)
+    }
+}
+
+impl<'a> Div<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem_big(other).0
+    }
+}
+
+impl<'a> Rem<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem_big(other).1
+    }
+}
+
+/// Implements an operator on owned BigUints by forwarding to the implementation on references.
+macro_rules! forward_by_value {
+    ($($op:ident::$method:ident),*) => {
+        $(
+            impl $op for BigUint {
+                type Output = BigUint;
+
+                fn $method(self, other: BigUint) -> BigUint {
+                    (&self).$method(&other)
+                }
+            }
+        )*
+    };
+}
+
+forward_by_value!(Add::add, Sub::sub, Mul::mul, Div::div, Rem::rem);
+
+// a BigUint can always grow to fit a sum or product, so only subtraction can fail
+
+impl CheckedAdd for BigUint {
+    fn checked_add(&self, other: &BigUint) -> Option<BigUint> {
+        Some(self + other)
+    }
+}
+
+impl CheckedSub for BigUint {
+    fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self >= other {
+            Some(self - other)
+        } else {
+            None
+        }
+    }
+}
+
+impl CheckedMul for BigUint {
+    fn checked_mul(&self, other: &BigUint) -> Option<BigUint> {
+        Some(self * other)
+    }
+}
+
+impl Zero for BigUint {
+    fn zero() -> Self {
+        Self { digits: Vec::new() }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+}
+
+impl One for BigUint {
+    fn one() -> Self {
+        Self { digits: vec![1] }
+    }
+}
+
+impl Num for BigUint {
+    type FromStrRadixErr = ParseBigUintError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseBigUintError> {
+        if s.is_empty() {
+            return Err(ParseBigUintError);
+        }
+
+        let radix_digit = Self::from(u64::from(radix));
+        s.chars().try_fold(Self::zero(), |n, c| {
+            let digit = c.to_digit(radix).ok_or(ParseBigUintError)?;
+            Ok(&(&n * &radix_digit) + &Self::from(u64::from(digit)))
+        })
+    }
+}
+
+/// Returned when parsing a BigUint from a string that isn't a number in the given radix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBigUintError;
+
+impl fmt::Display for ParseBigUintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigUintError {}
+
+impl Integer for BigUint {
+    fn div_floor(&self, other: &Self) -> Self {
+        self / other
+    }
+
+    fn mod_floor(&self, other: &Self) -> Self {
+        self % other
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+
+        a
+    }
+
+    fn lcm(&self, other: &Self) -> Self {
+        if self.is_zero() && other.is_zero() {
+            return Self::zero();
+        }
+
+        &(self / &self.gcd(other)) * other
+    }
+
+    fn divides(&self, other: &Self) -> bool {
+        self.is_multiple_of(other)
+    }
+
+    fn is_multiple_of(&self, other: &Self) -> bool {
+        if other.is_zero() {
+            return self.is_zero();
+        }
+
+        (self % other).is_zero()
+    }
+
+    fn is_even(&self) -> bool {
+        !self.is_odd()
+    }
+
+    fn is_odd(&self) -> bool {
+        self.digits.first().is_some_and(|digit| digit & 1 == 1)
+    }
+
+    fn div_rem(&self, other: &Self) -> (Self, Self) {
+        self.div_rem_big(other)
+    }
+}
+
+impl FromPrimitive for BigUint {
+    fn from_i64(n: i64) -> Option<Self> {
+        n.to_u64().map(Self::from)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Self::from(n))
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        n.to_u128().map(Self::from)
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(Self::from(n))
+    }
+}
+
+impl ToPrimitive for BigUint {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_u128().and_then(|n| n.to_i64())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|n| n.to_u64())
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.to_u128().and_then(|n| n.to_i128())
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        if self.digits.len() > 4 {
+            return None;
+        }
+
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0u128, |n, digit| n << 32 | u128::from(*digit)),
+        )
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0f64, |n, digit| n * 4_294_967_296.0 + f64::from(*digit)),
+        )
+    }
+}
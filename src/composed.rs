@@ -0,0 +1,620 @@
+use std::cmp::Ordering;
+
+use num_traits::{CheckedAdd, CheckedMul, One};
+
+use crate::element::Element;
+use crate::error::{OverflowError, SearchExhausted};
+use crate::facts::SetFacts;
+use crate::infinite_set::{take_merged, InfiniteSet};
+use crate::periodic::PeriodicSet;
+
+/// A union of two infinite sets that keeps their concrete types, unlike InfiniteUnion, which
+/// boxes them. Composing sets this way allocates nothing, lets the compiler inline through the
+/// sets, and works with sets that borrow data, since the sets don't have to be `'static`.
+///
+/// Like InfiniteUnion, the sets are merged in ascending order through their stored next values.
+/// Nothing is pulled from the sets until the union is first iterated.
+pub struct Union<A, B>
+where
+    A: InfiniteSet,
+{
+    first: A,
+    second: B,
+
+    /// The stored next values of both sets. A stored value of None means that its set has
+    /// ended.
+    first_next: Option<A::Item>,
+    second_next: Option<A::Item>,
+
+    /// Whether the next values have been pulled from the sets yet.
+    started: bool,
+}
+
+impl<A, B> Union<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord,
+{
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            first_next: None,
+            second_next: None,
+            started: false,
+        }
+    }
+}
+
+impl<A, B> InfiniteSet for Union<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord,
+{
+    fn contains(&self, x: &A::Item) -> bool {
+        self.first.contains(x) || self.second.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<A::Item> {
+        self.first.facts().union(self.second.facts())
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first.overflow().or_else(|| self.second.overflow())
+    }
+
+    fn seek(&mut self, lower_bound: &A::Item) -> Option<A::Item> {
+        // move each set whose next value is behind the bound up to it. before the union has
+        // been iterated, both sets can seek the bound directly
+        if !self.started {
+            self.first_next = self.first.seek(lower_bound);
+            self.second_next = self.second.seek(lower_bound);
+            self.started = true;
+        } else {
+            if self.first_next.as_ref().is_some_and(|x| x < lower_bound) {
+                self.first_next = self.first.seek(lower_bound);
+            }
+            if self.second_next.as_ref().is_some_and(|x| x < lower_bound) {
+                self.second_next = self.second.seek(lower_bound);
+            }
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        (self.first.estimated_density() + self.second.estimated_density()).min(1.0)
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<A::Item>> {
+        let first = self.first.as_periodic()?;
+        let second = self.second.as_periodic()?;
+
+        // the stored next values haven't been yielded yet, so each set resumes from its own
+        if self.started {
+            first
+                .resumed_at(self.first_next.as_ref())?
+                .collapse_union(&second.resumed_at(self.second_next.as_ref())?)
+        } else {
+            first.collapse_union(&second)
+        }
+    }
+}
+
+impl<A, B> Iterator for Union<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.first_next = self.first.next();
+            self.second_next = self.second.next();
+            self.started = true;
+        }
+
+        let (first, second) = (&mut self.first, &mut self.second);
+        take_merged(
+            &mut self.first_next,
+            &mut self.second_next,
+            || first.next(),
+            || second.next(),
+            Ordering::Less,
+        )
+    }
+}
+
+/// An intersection of two infinite sets that keeps their concrete types, unlike
+/// InfiniteIntersection, which boxes them. See `Union` for why that's useful.
+///
+/// Like InfiniteIntersection, the intersection is iterated with a leapfrog join that advances
+/// the sparser set and moves the other up to it, and it is known to be empty when the facts of
+/// the sets prove them disjoint.
+///
+/// WARNING: an empty intersection that can't be proven empty from the facts will stall the
+/// program when calling next(), unless one of the sets is finite or a search budget has been set
+/// with `with_budget`. Use `try_next_within` to search a bounded number of candidates instead.
+pub struct Intersection<A, B>
+where
+    A: InfiniteSet,
+{
+    first: A,
+    second: B,
+
+    /// The value each set was last moved to, or None if the set has to be advanced before the
+    /// search goes on.
+    first_head: Option<A::Item>,
+    second_head: Option<A::Item>,
+
+    /// Set when the facts of the sets prove that they have no elements in common.
+    known_empty: bool,
+
+    /// The most candidates next() will check before giving up, if any.
+    budget: Option<usize>,
+}
+
+impl<A, B> Intersection<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord,
+{
+    pub fn new(first: A, second: B) -> Self {
+        let known_empty = first.facts().is_disjoint(&second.facts());
+
+        Self {
+            first,
+            second,
+            first_head: None,
+            second_head: None,
+            known_empty,
+            budget: None,
+        }
+    }
+
+    /// Returns true if the intersection was proven empty from the facts of its sets.
+    pub fn is_known_empty(&self) -> bool {
+        self.known_empty
+    }
+
+    /// Limits every call to next() to checking at most `limit` candidates. If no element is
+    /// found within the budget, next() returns None instead of searching forever.
+    pub fn with_budget(mut self, limit: usize) -> Self {
+        self.budget = Some(limit);
+        self
+    }
+
+    /// Finds the next element of the intersection, checking at most `limit` candidates. A
+    /// candidate is a value that one of the sets has and the other has to be moved up to.
+    /// Rejected candidates are consumed, so calling this again resumes the search where it left
+    /// off.
+    ///
+    /// Returns Ok(None) if the intersection has ended, which is immediately the case for a
+    /// known-empty intersection, or if either of its sets has ended (for example by
+    /// overflowing).
+    pub fn try_next_within(&mut self, limit: usize) -> Result<Option<A::Item>, SearchExhausted> {
+        if self.known_empty {
+            return Ok(None);
+        }
+
+        for _ in 0..limit {
+            if self.fill_heads().is_none() {
+                return Ok(None);
+            }
+
+            // the set that is behind leaps up to the other, until they agree
+            let (first_head, second_head) = (
+                self.first_head
+                    .as_ref()
+                    .expect("the heads were just filled"),
+                self.second_head
+                    .as_ref()
+                    .expect("the heads were just filled"),
+            );
+            let moved = match first_head.cmp(second_head) {
+                Ordering::Equal => {
+                    self.second_head = None;
+                    return Ok(self.first_head.take());
+                }
+                Ordering::Less => {
+                    self.first_head = self.first.seek(second_head);
+                    self.first_head.is_some()
+                }
+                Ordering::Greater => {
+                    self.second_head = self.second.seek(first_head);
+                    self.second_head.is_some()
+                }
+            };
+            if !moved {
+                return Ok(None);
+            }
+        }
+
+        Err(SearchExhausted { candidates: limit })
+    }
+
+    /// Gives both sets a value to compare, advancing the sparser set on its own if neither has
+    /// one. Returns None if either set ends.
+    fn fill_heads(&mut self) -> Option<()> {
+        match (&self.first_head, &self.second_head) {
+            (Some(_), Some(_)) => {}
+            (Some(x), None) => self.second_head = Some(self.second.seek(x)?),
+            (None, Some(x)) => self.first_head = Some(self.first.seek(x)?),
+            (None, None) => {
+                if self.first.estimated_density() <= self.second.estimated_density() {
+                    let x = self.first.next()?;
+                    self.second_head = Some(self.second.seek(&x)?);
+                    self.first_head = Some(x);
+                } else {
+                    let x = self.second.next()?;
+                    self.first_head = Some(self.first.seek(&x)?);
+                    self.second_head = Some(x);
+                }
+            }
+        }
+
+        Some(())
+    }
+}
+
+impl<A, B> InfiniteSet for Intersection<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord,
+{
+    fn contains(&self, x: &A::Item) -> bool {
+        !self.known_empty && self.first.contains(x) && self.second.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<A::Item> {
+        self.first.facts().intersect(self.second.facts())
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first.overflow().or_else(|| self.second.overflow())
+    }
+
+    fn seek(&mut self, lower_bound: &A::Item) -> Option<A::Item> {
+        if self.known_empty {
+            return None;
+        }
+
+        // start the search with both sets at the bound or past it
+        if self.first_head.as_ref().is_none_or(|x| x < lower_bound) {
+            self.first_head = Some(self.first.seek(lower_bound)?);
+        }
+        if self.second_head.as_ref().is_none_or(|x| x < lower_bound) {
+            self.second_head = Some(self.second.seek(lower_bound)?);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        if self.known_empty {
+            return 0.0;
+        }
+
+        self.first.estimated_density() * self.second.estimated_density()
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<A::Item>> {
+        let resume = |periodic: PeriodicSet<A::Item>, head: &Option<A::Item>| match head {
+            Some(x) => periodic.resumed_at(Some(x)),
+            None => Some(periodic),
+        };
+
+        let first = resume(self.first.as_periodic()?, &self.first_head)?;
+        let second = resume(self.second.as_periodic()?, &self.second_head)?;
+        first.collapse_intersection(&second)
+    }
+}
+
+impl<A, B> Iterator for Intersection<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.budget {
+            Some(limit) => self.try_next_within(limit).ok().flatten(),
+            // without a budget the search goes on until it finds an element or a set ends
+            None => loop {
+                if let Ok(next) = self.try_next_within(usize::MAX) {
+                    break next;
+                }
+            },
+        }
+    }
+}
+
+/// The elements of a universe that satisfy a predicate, such as the positive integers whose
+/// digits add up to 7. It is created with `InfiniteSet::from_predicate`, and makes one-off sets
+/// possible without a struct and trait impls of their own.
+///
+/// Iterating the set walks the universe and skips the elements that fail the predicate, so the
+/// universe decides the order and where the set ends. The predicate has to give the same answer
+/// every time it is asked about a value, since `contains` asks it too.
+///
+/// WARNING: like InfiniteDifference, a PredicateSet can't tell when no more elements of its
+/// universe will satisfy the predicate. Calling next() once they have run out will stall the
+/// program, unless a search budget has been set with `with_budget`.
+pub struct PredicateSet<S, P> {
+    universe: S,
+    predicate: P,
+
+    /// The most candidates next() will check before giving up, if any.
+    budget: Option<usize>,
+}
+
+impl<S, P> PredicateSet<S, P>
+where
+    S: InfiniteSet,
+    P: Fn(&S::Item) -> bool,
+{
+    pub(crate) fn new(universe: S, predicate: P) -> Self {
+        Self {
+            universe,
+            predicate,
+            budget: None,
+        }
+    }
+
+    /// Limits every call to next() to checking at most `limit` candidates from the universe. If
+    /// no element is found within the budget, next() returns None instead of searching forever.
+    pub fn with_budget(mut self, limit: usize) -> Self {
+        self.budget = Some(limit);
+        self
+    }
+
+    /// Finds the next element of the set, checking at most `limit` candidates from the universe.
+    /// Rejected candidates are consumed, so calling this again resumes the search where it left
+    /// off.
+    ///
+    /// Returns Ok(None) if the set has ended because its universe has ended.
+    pub fn try_next_within(&mut self, limit: usize) -> Result<Option<S::Item>, SearchExhausted> {
+        for _ in 0..limit {
+            let x = match self.universe.next() {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            if (self.predicate)(&x) {
+                return Ok(Some(x));
+            }
+        }
+
+        Err(SearchExhausted { candidates: limit })
+    }
+}
+
+impl<S, P> InfiniteSet for PredicateSet<S, P>
+where
+    S: InfiniteSet,
+    P: Fn(&S::Item) -> bool,
+{
+    fn contains(&self, x: &S::Item) -> bool {
+        self.universe.contains(x) && (self.predicate)(x)
+    }
+
+    fn facts(&self) -> SetFacts<S::Item> {
+        // anything true of every element of the universe is true of the set's elements
+        self.universe.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.universe.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &S::Item) -> Option<S::Item>
+    where
+        S::Item: Ord,
+    {
+        // jump the universe to the bound, then carry on as usual if it landed on a value that
+        // fails the predicate
+        let x = self.universe.seek(lower_bound)?;
+        if (self.predicate)(&x) {
+            return Some(x);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // nothing is known about how often the predicate holds, so this is only an upper bound
+        self.universe.estimated_density()
+    }
+}
+
+impl<S, P> Iterator for PredicateSet<S, P>
+where
+    S: InfiniteSet,
+    P: Fn(&S::Item) -> bool,
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(limit) = self.budget {
+            return self.try_next_within(limit).ok().flatten();
+        }
+
+        let predicate = &self.predicate;
+        self.universe.find(|x| predicate(x))
+    }
+}
+
+/// The image of a set under a strictly increasing function: `f(x)` for every element x of the
+/// source set, in the same ascending order. It is created with `InfiniteSet::map_monotone`.
+/// Unlike `Iterator::map`, the image is still an InfiniteSet, so it can be combined with other
+/// sets.
+///
+/// `contains` asks `inverse` which element of the source could map to a value, then checks that
+/// the source contains it and that it does map to the value. The inverse can be a closed form,
+/// like an integer square root for `f(x) = x * x`, or a search over a fresh copy of the source.
+/// Since the answer is checked, the inverse only has to find the right candidate for values that
+/// are in the image; it is fine for it to round, or to return the first element that maps to at
+/// least the value. When there is no inverse to hand, `MonotoneImage::searching` builds one that
+/// searches the source for it.
+///
+/// `seek` resumes the source at the element the inverse gives and walks it from there, so an
+/// inverse that rounds has to round down, or at least never return an element past the first one
+/// that maps to the bound or above.
+pub struct MonotoneImage<S, F, G> {
+    source: S,
+    f: F,
+    inverse: G,
+}
+
+impl<S, F, G, U> MonotoneImage<S, F, G>
+where
+    S: InfiniteSet,
+    F: Fn(S::Item) -> U,
+    G: Fn(&U) -> Option<S::Item>,
+{
+    pub(crate) fn new(source: S, f: F, inverse: G) -> Self {
+        Self { source, f, inverse }
+    }
+}
+
+impl<S, F, U> MonotoneImage<S, F, Box<dyn Fn(&U) -> Option<S::Item>>>
+where
+    S: InfiniteSet + 'static,
+    S::Item: Element,
+    F: Fn(S::Item) -> U + Clone + 'static,
+    U: PartialOrd + 'static,
+{
+    /// Creates the image of a source set under `f` without an inverse. `fresh` creates the
+    /// source from its start; the image iterates one copy, and `contains` and `seek` find their
+    /// element of the source in another, by stepping it with `seek` until the mapped value
+    /// reaches or passes the one they are after. The steps double in length, so this takes
+    /// about twice the log of the distance in seeks, which is quick for sets with a fast `seek`.
+    pub fn searching<M>(fresh: M, f: F) -> Self
+    where
+        M: Fn() -> S + 'static,
+    {
+        let source = fresh();
+        let mapped = f.clone();
+        let inverse = move |y: &U| first_reaching(&fresh, &mapped, y);
+
+        Self::new(source, f, Box::new(inverse))
+    }
+}
+
+/// Returns the first element of a fresh source that `f` maps to at least `y`. The source is
+/// stepped with `seek` in steps that double until it reaches or passes `y`, and the last step is
+/// then halved until the first element that does is pinned down.
+fn first_reaching<S, F, U>(fresh: &impl Fn() -> S, f: &F, y: &U) -> Option<S::Item>
+where
+    S: InfiniteSet,
+    S::Item: Element,
+    F: Fn(S::Item) -> U,
+    U: PartialOrd,
+{
+    let reaches = |x: &S::Item| f(x.clone()) >= *y;
+
+    // the last element found that maps below y, if any, and the first found that doesn't
+    let mut below = None;
+    let mut source = fresh();
+    let mut x = source.next()?;
+    let mut step = S::Item::one();
+    while !reaches(&x) {
+        let bound = match x.checked_add(&step) {
+            Some(bound) => bound,
+            // near the end of the element type, the source is walked one element at a time
+            None => {
+                step = S::Item::one();
+                x.checked_add(&step)?
+            }
+        };
+        step = step.checked_mul(&S::Item::two()).unwrap_or(step);
+
+        below = Some(x);
+        x = source.seek(&bound)?;
+    }
+
+    // every element up to `low` maps below y, and the first element from `high` on doesn't
+    let mut low = match below {
+        Some(low) => low,
+        None => return Some(x),
+    };
+    let mut high = x.clone();
+    while high.clone() - low.clone() > S::Item::one() {
+        let middle = low.clone() + (high.clone() - low.clone()) / S::Item::two();
+        let candidate = fresh().seek(&middle)?;
+        if reaches(&candidate) {
+            high = middle;
+            x = candidate;
+        } else {
+            low = candidate;
+        }
+    }
+
+    Some(x)
+}
+
+impl<S, F, G, U> InfiniteSet for MonotoneImage<S, F, G>
+where
+    S: InfiniteSet,
+    S::Item: Ord,
+    F: Fn(S::Item) -> U,
+    G: Fn(&U) -> Option<S::Item>,
+    U: PartialEq,
+{
+    fn contains(&self, y: &U) -> bool {
+        (self.inverse)(y).is_some_and(|x| self.source.contains(&x) && (self.f)(x) == *y)
+    }
+
+    fn facts(&self) -> SetFacts<U> {
+        // f keeps the order, so it takes the bounds of the source to the bounds of the image.
+        // residues don't survive it
+        let facts = self.source.facts();
+        SetFacts {
+            empty: facts.empty,
+            lower_bound: facts.lower_bound.map(&self.f),
+            upper_bound: facts.upper_bound.map(&self.f),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.source.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &U) -> Option<U>
+    where
+        U: Ord,
+    {
+        // jump the source to the inverse's candidate, then walk the rest of the way in case the
+        // inverse rounded down
+        let mut y = match (self.inverse)(lower_bound) {
+            Some(x) => (self.f)(self.source.seek(&x)?),
+            None => self.next()?,
+        };
+        while y < *lower_bound {
+            y = self.next()?;
+        }
+
+        Some(y)
+    }
+}
+
+impl<S, F, G, U> Iterator for MonotoneImage<S, F, G>
+where
+    S: InfiniteSet,
+    F: Fn(S::Item) -> U,
+    G: Fn(&U) -> Option<S::Item>,
+{
+    type Item = U;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.source.next().map(&self.f)
+    }
+}
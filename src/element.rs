@@ -0,0 +1,33 @@
+use num_integer::Integer;
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, ToPrimitive};
+
+/// The numeric types that the built-in sets can be made of. Any integer type that can be
+/// converted to and from the primitive integers qualifies, which covers all of the primitive
+/// integers (u32, u64, u128, i64, ...) as well as arbitrary-precision integers.
+///
+/// The checked arithmetic is what lets a set notice that its next element won't fit in the type,
+/// so that it can apply its OverflowPolicy instead of wrapping around.
+///
+/// Element is implemented automatically for every type that meets its requirements.
+pub trait Element:
+    Integer + Clone + FromPrimitive + ToPrimitive + CheckedAdd + CheckedSub + CheckedMul
+{
+    /// Returns the element 2, which many of the sets step by.
+    fn two() -> Self {
+        Self::one() + Self::one()
+    }
+
+    /// Returns true if the type has values past u128::MAX, as arbitrary-precision integers do.
+    /// The sets that do their arithmetic in u128 use this to tell the end of what they can
+    /// compute apart from the end of the element type.
+    fn exceeds_u128() -> bool {
+        Self::from_u128(u128::MAX)
+            .and_then(|max| max.checked_add(&Self::one()))
+            .is_some()
+    }
+}
+
+impl<T> Element for T where
+    T: Integer + Clone + FromPrimitive + ToPrimitive + CheckedAdd + CheckedSub + CheckedMul
+{
+}
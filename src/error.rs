@@ -0,0 +1,44 @@
+use std::error::Error;
+use std::fmt;
+
+/// Returned by bounded searches (such as `InfiniteIntersection::try_next_within`) that gave up
+/// before finding an element.
+///
+/// This doesn't prove that no further element exists, only that none was found among the
+/// candidates that were checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchExhausted {
+    /// How many candidates were checked and rejected before giving up.
+    pub candidates: usize,
+}
+
+impl fmt::Display for SearchExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no element found within {} candidates", self.candidates)
+    }
+}
+
+impl Error for SearchExhausted {}
+
+/// Reported by a set that ended because its next element would not fit in its element type.
+/// Combinators report the OverflowError of whichever of their sets overflowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    /// The name of the set that overflowed, such as "InfiniteEvens".
+    pub set: &'static str,
+
+    /// The name of the element type that was overflowed, such as "u128".
+    pub element: &'static str,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ran past the largest value of {}",
+            self.set, self.element
+        )
+    }
+}
+
+impl Error for OverflowError {}
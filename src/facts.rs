@@ -0,0 +1,165 @@
+/// A residue class: every integer congruent to `remainder` modulo `modulus`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Residue {
+    modulus: u128,
+    remainder: u128,
+}
+
+impl Residue {
+    /// Creates the residue class of `remainder` modulo `modulus`. The remainder is reduced, so
+    /// `Residue::new(2, 3)` is the same class as `Residue::new(2, 1)`.
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn new(modulus: u128, remainder: u128) -> Self {
+        assert!(modulus > 0, "residue class modulus must be positive");
+
+        Self {
+            modulus,
+            remainder: remainder % modulus,
+        }
+    }
+
+    pub fn modulus(&self) -> u128 {
+        self.modulus
+    }
+
+    pub fn remainder(&self) -> u128 {
+        self.remainder
+    }
+
+    /// Returns true if `x` is in this residue class.
+    pub fn contains(&self, x: u128) -> bool {
+        x % self.modulus == self.remainder
+    }
+
+    /// Returns true if no integer is in both this residue class and the other. Two classes share
+    /// an integer exactly when their remainders agree modulo the gcd of their moduli.
+    pub fn is_disjoint(&self, other: &Residue) -> bool {
+        let g = gcd(self.modulus, other.modulus);
+
+        self.remainder % g != other.remainder % g
+    }
+
+    /// Returns the smallest residue class containing both this class and the other, or None if
+    /// that class is every integer (modulus 1) and so says nothing.
+    pub fn join(&self, other: &Residue) -> Option<Residue> {
+        let difference = self.remainder.abs_diff(other.remainder);
+        let g = gcd(gcd(self.modulus, other.modulus), difference);
+
+        if g > 1 {
+            Some(Residue::new(g, self.remainder))
+        } else {
+            None
+        }
+    }
+}
+
+/// Structural facts that an InfiniteSet knows about itself, regardless of how far it has been
+/// iterated. Every fact is a guarantee about all of the set's elements, so a set that knows
+/// nothing can always return `SetFacts::default()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetFacts<T> {
+    /// The set has no elements at all.
+    pub empty: bool,
+
+    /// Every element of the set is in this residue class.
+    pub residue: Option<Residue>,
+
+    /// No element of the set is less than this.
+    pub lower_bound: Option<T>,
+
+    /// No element of the set is greater than this. Since every set starts from its least
+    /// element, a set with an upper bound is finite.
+    pub upper_bound: Option<T>,
+}
+
+impl<T> Default for SetFacts<T> {
+    fn default() -> Self {
+        Self {
+            empty: false,
+            residue: None,
+            lower_bound: None,
+            upper_bound: None,
+        }
+    }
+}
+
+impl<T: Ord> SetFacts<T> {
+    /// Returns true if the facts prove that no element can be in both sets they describe.
+    pub fn is_disjoint(&self, other: &SetFacts<T>) -> bool {
+        if self.empty || other.empty {
+            return true;
+        }
+
+        // one set ends before the other starts
+        let below = |upper: &Option<T>, lower: &Option<T>| match (upper, lower) {
+            (Some(upper), Some(lower)) => upper < lower,
+            _ => false,
+        };
+        if below(&self.upper_bound, &other.lower_bound)
+            || below(&other.upper_bound, &self.lower_bound)
+        {
+            return true;
+        }
+
+        match (&self.residue, &other.residue) {
+            (Some(a), Some(b)) => a.is_disjoint(b),
+            _ => false,
+        }
+    }
+
+    /// Facts that hold for the intersection of the sets described by `self` and `other`.
+    pub fn intersect(self, other: SetFacts<T>) -> SetFacts<T> {
+        let empty = self.is_disjoint(&other);
+
+        Self {
+            empty,
+            // anything true of either operand is true of the intersection
+            residue: self.residue.or(other.residue),
+            lower_bound: match (self.lower_bound, other.lower_bound) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            },
+            upper_bound: match (self.upper_bound, other.upper_bound) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            },
+        }
+    }
+
+    /// Facts that hold for the union of the sets described by `self` and `other`.
+    pub fn union(self, other: SetFacts<T>) -> SetFacts<T> {
+        // an empty operand contributes nothing to the union
+        if self.empty {
+            return other;
+        } else if other.empty {
+            return self;
+        }
+
+        Self {
+            empty: false,
+            residue: match (self.residue, other.residue) {
+                (Some(a), Some(b)) => a.join(&b),
+                _ => None,
+            },
+            lower_bound: match (self.lower_bound, other.lower_bound) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                _ => None,
+            },
+            upper_bound: match (self.upper_bound, other.upper_bound) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+
+    a
+}
@@ -0,0 +1,593 @@
+use crate::element::Element;
+use crate::error::OverflowError;
+use crate::facts::SetFacts;
+use crate::infinite_set::InfiniteSet;
+use crate::overflow::{OverflowGuard, OverflowPolicy};
+use crate::roots;
+
+/// The families of figurate numbers. Every family has an n-th element for n = 1, 2, ..., and the
+/// elements grow with n, so the family can be iterated by counting n up.
+#[derive(Clone)]
+enum Shape<T> {
+    /// n^k for the given k.
+    Power(u32),
+
+    /// The s-gonal numbers n + (s - 2) * n(n - 1)/2, holding s - 2.
+    Polygonal(T),
+
+    /// The s-gonal pyramidal numbers, the sums of the first n s-gonal numbers, which come to
+    /// n(n + 1)((s - 2)(n - 1) + 3)/6. Holds s - 2.
+    Pyramidal(T),
+}
+
+impl<T: Element> Shape<T> {
+    /// Returns the n-th element of the family, or None if it doesn't fit in T.
+    fn term(&self, n: &T) -> Option<T> {
+        match self {
+            Shape::Power(k) => roots::checked_pow(n, *k),
+            Shape::Polygonal(a) => {
+                let pairs = half_product(&(n.clone() - T::one()), n)?;
+                a.checked_mul(&pairs)?.checked_add(n)
+            }
+            Shape::Pyramidal(a) => {
+                let three = T::two() + T::one();
+                let triangle = half_product(n, &n.checked_add(&T::one())?)?;
+                let factor = a
+                    .checked_mul(&(n.clone() - T::one()))?
+                    .checked_add(&three)?;
+
+                // the product is a multiple of 3, so one of its factors is. dividing that one
+                // first keeps the product from overflowing before the element does
+                if triangle.is_multiple_of(&three) {
+                    (triangle / three).checked_mul(&factor)
+                } else {
+                    triangle.checked_mul(&(factor / three))
+                }
+            }
+        }
+    }
+
+    /// Estimates the n whose element is x by solving the family's formula for n, or returns None
+    /// if there is no estimate to go on. Powers have an exact integer root, while the others are
+    /// solved with floating point arithmetic.
+    fn estimate_index(&self, x: &T) -> Option<T> {
+        let estimate = match self {
+            Shape::Power(k) => return Some(roots::root(x, *k)),
+            Shape::Polygonal(a) => {
+                // the positive root of (s - 2)n^2 - (s - 4)n - 2x = 0
+                let a = a.to_f64()?;
+                let b = a - 2.0;
+                (b + (b * b + 8.0 * a * x.to_f64()?).sqrt()) / (2.0 * a)
+            }
+            Shape::Pyramidal(a) => (6.0 * x.to_f64()? / a.to_f64()?).cbrt(),
+        };
+
+        Some(estimate)
+            .filter(|estimate| estimate.is_finite())
+            .and_then(|estimate| T::from_f64(estimate.floor()))
+    }
+
+    /// Returns the least n whose element is at least x. The search starts from the estimate of
+    /// `estimate_index`, which is usually exact or close, and gallops away from it in doubling
+    /// steps until it brackets n, so that even a poor estimate only costs a few steps.
+    fn index_at_least(&self, x: &T) -> T {
+        // an element that doesn't fit in T is certainly at least x
+        let reaches = |n: &T| self.term(n).is_none_or(|term| term >= *x);
+
+        let mut n = self
+            .estimate_index(x)
+            .filter(|n| *n > T::zero())
+            .unwrap_or_else(T::one);
+
+        // bracket the index between one that doesn't reach x (or 0) and one that does
+        let (mut below, mut above) = if reaches(&n) {
+            let mut step = T::one();
+            loop {
+                if n <= step {
+                    break (T::zero(), n);
+                }
+                let lower = n.clone() - step.clone();
+                if !reaches(&lower) {
+                    break (lower, n);
+                }
+                n = lower;
+                step = step * T::two();
+            }
+        } else {
+            let mut step = T::one();
+            loop {
+                // n is below an element that fits in T, so at least n + 1 fits too
+                let higher = n.checked_add(&step).unwrap_or_else(|| n.clone() + T::one());
+                if reaches(&higher) {
+                    break (n, higher);
+                }
+                n = higher;
+                step = step * T::two();
+            }
+        };
+
+        while above.clone() - below.clone() > T::one() {
+            let middle = below.clone() + (above.clone() - below.clone()) / T::two();
+            if reaches(&middle) {
+                above = middle;
+            } else {
+                below = middle;
+            }
+        }
+
+        above
+    }
+}
+
+/// Returns a * b / 2 for consecutive integers a and b, or None if it doesn't fit in T. One of them
+/// is even, and it is halved first so that the product only overflows if the result does.
+fn half_product<T: Element>(a: &T, b: &T) -> Option<T> {
+    if a.is_even() {
+        (a.clone() / T::two()).checked_mul(b)
+    } else {
+        a.checked_mul(&(b.clone() / T::two()))
+    }
+}
+
+/// What the figurate sets share: the elements of a Shape, iterated by counting up their index.
+struct Figurate<T> {
+    shape: Shape<T>,
+
+    /// The index of `next`.
+    n: T,
+
+    /// The next element to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    /// The name of the set, for its OverflowError.
+    name: &'static str,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element> Figurate<T> {
+    fn new(shape: Shape<T>, name: &'static str) -> Self {
+        Self {
+            next: shape.term(&T::one()),
+            n: T::one(),
+            shape,
+            name,
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element> InfiniteSet for Figurate<T> {
+    fn contains(&self, x: &T) -> bool {
+        *x > T::zero() && self.shape.term(&self.shape.index_at_least(x)).as_ref() == Some(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            lower_bound: Some(T::one()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        // jump straight to the first index whose element reaches the bound
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.n = self.shape.index_at_least(lower_bound);
+            self.next = self.shape.term(&self.n);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // there is one element in the gap between the next element and the one after it
+        let following = self.shape.term(&(self.n.clone() + T::one()));
+        let near = self.next.as_ref().and_then(T::to_f64);
+        match (near, following.as_ref().and_then(T::to_f64)) {
+            (Some(near), Some(following)) => 1.0 / (following - near).max(1.0),
+            _ => 0.0,
+        }
+    }
+}
+
+impl<T: Element> Iterator for Figurate<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>(self.name),
+        };
+
+        // the index is smaller than its element, so it can't overflow before the element does
+        self.n = self.n.clone() + T::one();
+        self.next = self.shape.term(&self.n);
+
+        Some(current)
+    }
+}
+
+/// Infinite set of the k-th powers of the positive integers (1, 2^k, 3^k, ...). `contains` takes
+/// an exact integer k-th root, and `seek` jumps straight to the root of its bound.
+pub struct InfiniteKthPowers<T = u128> {
+    terms: Figurate<T>,
+    exponent: u32,
+}
+
+impl<T: Element> InfiniteKthPowers<T> {
+    /// Creates the set of k-th powers for k = `exponent`.
+    ///
+    /// Panics if `exponent` is less than 2, since the first powers are just the positive ints.
+    pub fn new(exponent: u32) -> Self {
+        assert!(
+            exponent >= 2,
+            "the exponent of InfiniteKthPowers must be at least 2"
+        );
+
+        Self {
+            terms: Figurate::new(Shape::Power(exponent), "InfiniteKthPowers"),
+            exponent,
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            terms: self.terms.with_overflow_policy(policy),
+            ..self
+        }
+    }
+
+    pub fn exponent(&self) -> u32 {
+        self.exponent
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteKthPowers<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.terms.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.terms.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.terms.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.terms.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.terms.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfiniteKthPowers<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.terms.next()
+    }
+}
+
+/// Infinite set of perfect squares. This is `InfiniteKthPowers` with an exponent of 2.
+pub struct InfiniteSquares<T = u128> {
+    powers: InfiniteKthPowers<T>,
+}
+
+impl<T: Element> InfiniteSquares<T> {
+    pub fn new() -> Self {
+        Self {
+            powers: InfiniteKthPowers::new(2),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            powers: self.powers.with_overflow_policy(policy),
+        }
+    }
+}
+
+impl<T: Element> Default for InfiniteSquares<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteSquares<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.powers.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.powers.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.powers.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.powers.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.powers.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfiniteSquares<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.powers.next()
+    }
+}
+
+/// Infinite set of perfect cubes. This is `InfiniteKthPowers` with an exponent of 3.
+pub struct InfiniteCubes<T = u128> {
+    powers: InfiniteKthPowers<T>,
+}
+
+impl<T: Element> InfiniteCubes<T> {
+    pub fn new() -> Self {
+        Self {
+            powers: InfiniteKthPowers::new(3),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            powers: self.powers.with_overflow_policy(policy),
+        }
+    }
+}
+
+impl<T: Element> Default for InfiniteCubes<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteCubes<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.powers.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.powers.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.powers.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.powers.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.powers.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfiniteCubes<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.powers.next()
+    }
+}
+
+/// Infinite set of the s-gonal numbers, the numbers of dots in the regular polygons with s sides
+/// (for s = 5: 1, 5, 12, 22, ...). The n-th one is ((s - 2)n^2 - (s - 4)n)/2. `contains` and
+/// `seek` solve that quadratic for n, and check the answer with exact integer arithmetic.
+pub struct InfinitePolygonalNumbers<T = u128> {
+    terms: Figurate<T>,
+    sides: T,
+}
+
+impl<T: Element> InfinitePolygonalNumbers<T> {
+    /// Creates the set of polygonal numbers for polygons with the given number of sides.
+    ///
+    /// Panics if `sides` is less than 3.
+    pub fn new(sides: T) -> Self {
+        assert!(
+            sides > T::two(),
+            "InfinitePolygonalNumbers needs polygons of at least 3 sides"
+        );
+
+        Self {
+            terms: Figurate::new(
+                Shape::Polygonal(sides.clone() - T::two()),
+                "InfinitePolygonalNumbers",
+            ),
+            sides,
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            terms: self.terms.with_overflow_policy(policy),
+            ..self
+        }
+    }
+
+    pub fn sides(&self) -> &T {
+        &self.sides
+    }
+}
+
+impl<T: Element> InfiniteSet for InfinitePolygonalNumbers<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.terms.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.terms.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.terms.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.terms.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.terms.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfinitePolygonalNumbers<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.terms.next()
+    }
+}
+
+/// Infinite set of triangular numbers (1, 3, 6, 10, ...). This is `InfinitePolygonalNumbers`
+/// with 3 sides.
+pub struct InfiniteTriangularNumbers<T = u128> {
+    polygonal: InfinitePolygonalNumbers<T>,
+}
+
+impl<T: Element> InfiniteTriangularNumbers<T> {
+    pub fn new() -> Self {
+        Self {
+            polygonal: InfinitePolygonalNumbers::new(T::two() + T::one()),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            polygonal: self.polygonal.with_overflow_policy(policy),
+        }
+    }
+}
+
+impl<T: Element> Default for InfiniteTriangularNumbers<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteTriangularNumbers<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.polygonal.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.polygonal.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.polygonal.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.polygonal.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.polygonal.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfiniteTriangularNumbers<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.polygonal.next()
+    }
+}
+
+/// Infinite set of the s-gonal pyramidal numbers, the numbers of balls in pyramids stacked from
+/// s-gonal layers: the sums of the first n s-gonal numbers. For s = 3 these are the tetrahedral
+/// numbers (1, 4, 10, 20, ...) and for s = 4 the square pyramidal numbers (1, 5, 14, 30, ...).
+/// The n-th one is n(n + 1)((s - 2)n - (s - 5))/6, which `contains` and `seek` invert with a
+/// cube root estimate that is checked with exact integer arithmetic.
+pub struct InfinitePyramidalNumbers<T = u128> {
+    terms: Figurate<T>,
+    sides: T,
+}
+
+impl<T: Element> InfinitePyramidalNumbers<T> {
+    /// Creates the set of pyramidal numbers for layers with the given number of sides.
+    ///
+    /// Panics if `sides` is less than 3.
+    pub fn new(sides: T) -> Self {
+        assert!(
+            sides > T::two(),
+            "InfinitePyramidalNumbers needs layers of at least 3 sides"
+        );
+
+        Self {
+            terms: Figurate::new(
+                Shape::Pyramidal(sides.clone() - T::two()),
+                "InfinitePyramidalNumbers",
+            ),
+            sides,
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            terms: self.terms.with_overflow_policy(policy),
+            ..self
+        }
+    }
+
+    pub fn sides(&self) -> &T {
+        &self.sides
+    }
+}
+
+impl<T: Element> InfiniteSet for InfinitePyramidalNumbers<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.terms.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.terms.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.terms.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.terms.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.terms.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfinitePyramidalNumbers<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.terms.next()
+    }
+}
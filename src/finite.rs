@@ -0,0 +1,181 @@
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+use std::ops::{Bound, Range};
+
+use crate::element::Element;
+use crate::facts::SetFacts;
+use crate::infinite_set::InfiniteSet;
+
+/// A finite set of elements, such as a `BTreeSet`, adapted so that it can be combined with
+/// infinite sets. Its iterator yields the elements in ascending order and then ends.
+///
+/// The combinators carry on without a set once it has ended, so a union with a FiniteSet
+/// continues with the other sets, while an intersection with one is finite too and ends with it.
+pub struct FiniteSet<T> {
+    elements: BTreeSet<T>,
+
+    /// The next element to return, or None once every element has been returned.
+    next: Option<T>,
+}
+
+impl<T: Ord + Clone> FiniteSet<T> {
+    /// Creates the set of `elements`. They can be in any order, and repeats are dropped.
+    pub fn new(elements: impl IntoIterator<Item = T>) -> Self {
+        Self::from(elements.into_iter().collect::<BTreeSet<T>>())
+    }
+
+    /// The number of elements in the set, including those already iterated.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl<T: Ord + Clone> From<BTreeSet<T>> for FiniteSet<T> {
+    fn from(elements: BTreeSet<T>) -> Self {
+        Self {
+            next: elements.iter().next().cloned(),
+            elements,
+        }
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for FiniteSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl<T: Ord + Clone> InfiniteSet for FiniteSet<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.elements.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            empty: self.elements.is_empty(),
+            lower_bound: self.elements.iter().next().cloned(),
+            upper_bound: self.elements.iter().next_back().cloned(),
+            ..SetFacts::default()
+        }
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.next = self.elements.range(lower_bound..).next().cloned();
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // a finite set has no elements at all past some point, which also makes it the set
+        // that intersections advance, so that they end as soon as it does
+        0.0
+    }
+}
+
+impl<T: Ord + Clone> Iterator for FiniteSet<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+
+        // look up the element after the current one for next time
+        self.next = self
+            .elements
+            .range((Bound::Excluded(&current), Bound::Unbounded))
+            .next()
+            .cloned();
+
+        Some(current)
+    }
+}
+
+/// The set of integers from `start` up to but not including `end`, like a `Range`, but without
+/// materializing its elements like a FiniteSet would. See FiniteSet for how it combines with
+/// infinite sets.
+pub struct FiniteRange<T = u128> {
+    start: T,
+    end: T,
+
+    /// The next int to return, or None once the range has ended.
+    next: Option<T>,
+}
+
+impl<T: Element> FiniteRange<T> {
+    /// Creates the range of integers at least `start` and less than `end`. It is empty if `end`
+    /// is not greater than `start`.
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            next: if start < end {
+                Some(start.clone())
+            } else {
+                None
+            },
+            start,
+            end,
+        }
+    }
+
+    pub fn start(&self) -> &T {
+        &self.start
+    }
+
+    pub fn end(&self) -> &T {
+        &self.end
+    }
+}
+
+impl<T: Element> From<Range<T>> for FiniteRange<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(range.start, range.end)
+    }
+}
+
+impl<T: Element> InfiniteSet for FiniteRange<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.start <= *x && *x < self.end
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        if self.start >= self.end {
+            return SetFacts {
+                empty: true,
+                ..SetFacts::default()
+            };
+        }
+
+        SetFacts {
+            lower_bound: Some(self.start.clone()),
+            upper_bound: Some(self.end.clone() - T::one()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        // every int past the current one is in the range until it ends, so the bound itself is
+        // next
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.next = Some(lower_bound.clone());
+        }
+
+        self.next()
+    }
+}
+
+impl<T: Element> Iterator for FiniteRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take().filter(|current| *current < self.end)?;
+
+        // the next int is at most the end, so it always fits in T
+        self.next = Some(current.clone() + T::one());
+
+        Some(current)
+    }
+}
@@ -1,6 +1,13 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
 use std::hash::Hash;
 
+use crate::composed::{MonotoneImage, PredicateSet};
+use crate::error::{OverflowError, SearchExhausted};
+use crate::facts::SetFacts;
+use crate::periodic::PeriodicSet;
+use crate::sets::ArithmeticProgression;
+
 /// The InfiniteSet trait. Uses an Iterator design to return an infinite set of types. The trait
 /// requires an implementation of Iterator, but users should be careful not to attempt to collect
 /// for iterate over the entire set. It is infinite after all!
@@ -13,6 +20,84 @@ pub trait InfiniteSet: Iterator {
     /// be in the set. This function is probably impossible to call with an incompatible type.
     fn contains(&self, x: &<Self as Iterator>::Item) -> bool;
 
+    /// Structural facts about this set, such as a residue class or a lower bound that all of its
+    /// elements share. Combinators use these to reason about sets without iterating them, for
+    /// example to prove an intersection empty. Sets that know nothing about themselves can rely
+    /// on the default, which claims nothing.
+    fn facts(&self) -> SetFacts<<Self as Iterator>::Item> {
+        SetFacts::default()
+    }
+
+    /// Returns the OverflowError if this set ended because its next element would not fit in its
+    /// element type, or None if it hasn't. Combinators report the overflow of any of their sets.
+    /// Sets that can't overflow can rely on the default, which always returns None.
+    fn overflow(&self) -> Option<OverflowError> {
+        None
+    }
+
+    /// Advances the set to its least element that is at least `lower_bound`, then consumes and
+    /// returns it like next(). A set never moves backwards, so if it is already past the bound
+    /// this is the same as next().
+    ///
+    /// Combinators use this to jump over elements instead of walking through them. The default
+    /// does walk through them one at a time, so sets that can jump straight to an element
+    /// should override it.
+    fn seek(&mut self, lower_bound: &<Self as Iterator>::Item) -> Option<<Self as Iterator>::Item>
+    where
+        <Self as Iterator>::Item: Ord,
+    {
+        loop {
+            let x = self.next()?;
+            if x >= *lower_bound {
+                return Some(x);
+            }
+        }
+    }
+
+    /// Estimates the fraction of the integers near the elements this set is up to that are in
+    /// the set, from 0 (none of them) to 1 (all of them). For example, the even numbers have a
+    /// density of 1/2 everywhere, while the density of the primes near n is about 1/ln(n).
+    ///
+    /// Intersections advance their sparsest set and leap the others up to it, so that they
+    /// never walk through a dense set. Sets that don't know their density can rely on the
+    /// default, which assumes the worst and returns 1.
+    fn estimated_density(&self) -> f64 {
+        1.0
+    }
+
+    /// Returns the elements that this set has left to yield as a PeriodicSet, if they are
+    /// exactly the positive integers in some residue classes. Combinators collapse periodic
+    /// operands into a single PeriodicSet instead of combining them element by element. Sets that
+    /// aren't periodic can rely on the default, which returns None.
+    ///
+    /// The PeriodicSet has to behave like the set: it should report overflow under the set's
+    /// name with the set's policy, and so sets of element types past u128::MAX, where a
+    /// PeriodicSet ends, shouldn't describe themselves as one.
+    fn as_periodic(&self) -> Option<PeriodicSet<<Self as Iterator>::Item>> {
+        None
+    }
+
+    /// Returns this set as an ArithmeticProgression, if it is one, so that other progressions
+    /// can intersect it in closed form (see `intersect_closed_form`). Sets that aren't
+    /// progressions can rely on the default, which returns None.
+    fn as_progression(&self) -> Option<ArithmeticProgression<<Self as Iterator>::Item>> {
+        None
+    }
+
+    /// Returns the elements that both this set and `other` have left to yield, if this set can
+    /// find them without searching, as an ArithmeticProgression can for another progression.
+    /// InfiniteIntersection tries this before it falls back to searching its sets. Sets that
+    /// can't can rely on the default, which returns None.
+    fn intersect_closed_form(
+        &self,
+        _other: &dyn InfiniteSet<Item = <Self as Iterator>::Item>,
+    ) -> Option<Box<dyn InfiniteSet<Item = <Self as Iterator>::Item>>>
+    where
+        <Self as Iterator>::Item: 'static,
+    {
+        None
+    }
+
     /// Returns a InfiniteUnion between this set and another.
     fn union<I>(self, other: I) -> InfiniteUnion<Self::Item>
     where
@@ -26,192 +111,891 @@ pub trait InfiniteSet: Iterator {
     /// Returns an InfiniteIntersection between this set and another.
     fn intersect<I>(self, other: I) -> InfiniteIntersection<Self::Item>
     where
+        <Self as Iterator>::Item: Ord,
         Self: Sized + 'static,
         I: InfiniteSet<Item = Self::Item> + 'static,
     {
         InfiniteIntersection::from_sets(self, other)
     }
+
+    /// Returns an InfiniteDifference containing the elements of this set that are not in the
+    /// other.
+    fn difference<I>(self, other: I) -> InfiniteDifference<Self::Item>
+    where
+        Self: Sized + 'static,
+        I: InfiniteSet<Item = Self::Item> + 'static,
+    {
+        InfiniteDifference::from_sets(self, other)
+    }
+
+    /// Returns the complement of this set within `universe`: every element of the universe that
+    /// is not in this set. This is the difference of the universe and this set, so iterating it
+    /// walks the universe and skips the members of this set.
+    ///
+    /// For example, `InfiniteTwoPowers::new().complement_in(InfinitePositiveInts::new())` is the
+    /// set of positive integers that are not powers of two.
+    fn complement_in<U>(self, universe: U) -> InfiniteDifference<Self::Item>
+    where
+        Self: Sized + 'static,
+        U: InfiniteSet<Item = Self::Item> + 'static,
+    {
+        InfiniteDifference::from_sets(universe, self)
+    }
+
+    /// Returns the set of the elements of `universe` that satisfy `predicate`, so that a one-off
+    /// set only takes a closure. `contains` checks both that the universe contains a value and
+    /// that the value satisfies the predicate. Call it through the trait, as in
+    /// `InfiniteSet::from_predicate(InfinitePositiveInts::new(), |x| x % 10 == 7)`.
+    fn from_predicate<P>(universe: Self, predicate: P) -> PredicateSet<Self, P>
+    where
+        Self: Sized,
+        P: Fn(&<Self as Iterator>::Item) -> bool,
+    {
+        PredicateSet::new(universe, predicate)
+    }
+
+    /// Returns the image of this set under `f`, which must be strictly increasing so that the
+    /// image stays in ascending order. `inverse` gives `contains` the element of this set that
+    /// could map to a value (see MonotoneImage). For example, the odd squares are
+    /// `InfiniteOdds::new().map_monotone(|x| x * x, |y| Some(y.isqrt()))`. Without an inverse,
+    /// use `MonotoneImage::searching`.
+    fn map_monotone<U, F, G>(self, f: F, inverse: G) -> MonotoneImage<Self, F, G>
+    where
+        Self: Sized,
+        F: Fn(<Self as Iterator>::Item) -> U,
+        G: Fn(&U) -> Option<<Self as Iterator>::Item>,
+    {
+        MonotoneImage::new(self, f, inverse)
+    }
+
+    /// Returns an InfiniteSymmetricDifference containing the elements that are in exactly one of
+    /// this set and the other.
+    fn symmetric_difference<I>(self, other: I) -> InfiniteSymmetricDifference<Self::Item>
+    where
+        <Self as Iterator>::Item: Ord,
+        Self: Sized + 'static,
+        I: InfiniteSet<Item = Self::Item> + 'static,
+    {
+        InfiniteSymmetricDifference::from_sets(self, other)
+    }
+}
+
+/// Boxed sets are sets too, which lets sets of different types be combined with `from_many`.
+impl<S: InfiniteSet + ?Sized> InfiniteSet for Box<S> {
+    fn contains(&self, x: &S::Item) -> bool {
+        (**self).contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<S::Item> {
+        (**self).facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        (**self).overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &S::Item) -> Option<S::Item>
+    where
+        S::Item: Ord,
+    {
+        (**self).seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        (**self).estimated_density()
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<S::Item>> {
+        (**self).as_periodic()
+    }
+
+    fn as_progression(&self) -> Option<ArithmeticProgression<S::Item>> {
+        (**self).as_progression()
+    }
+
+    fn intersect_closed_form(
+        &self,
+        other: &dyn InfiniteSet<Item = S::Item>,
+    ) -> Option<Box<dyn InfiniteSet<Item = S::Item>>>
+    where
+        S::Item: 'static,
+    {
+        (**self).intersect_closed_form(other)
+    }
 }
 
-/// A union between two infinite sets. InfiniteUnion is also an InfiniteSet.
+/// A union of any number of infinite sets. InfiniteUnion is also an InfiniteSet.
+///
+/// The sets are merged through a heap of their next values, so each element costs O(log k) for
+/// k sets. We store the next values because simply comparing the results of next() on each set
+/// would unfairly throw away a value from one of the sets and exclude the value from the union.
+/// A set that has ended (for example by overflowing) drops out of the heap, and the union
+/// carries on with the others.
 ///
-/// first_next and second_next are the stored next values in the iterators. We store them because
-/// simply comparing the results of next() on each set would unfairly throw away a value from one
-/// of the sets and exclude the value from the union.
+/// Creating a union does no work: nothing is pulled from the sets until the union is first
+/// iterated, so building large expressions out of unions stays cheap.
 pub struct InfiniteUnion<T>
 where
     T: Ord,
 {
-    first_set: Box<dyn InfiniteSet<Item = T>>,
-    second_set: Box<dyn InfiniteSet<Item = T>>,
+    sets: Vec<Box<dyn InfiniteSet<Item = T>>>,
 
-    first_next: T,
-    second_next: T,
+    /// The next value of every set that hasn't ended, along with the index of its set. Reversed
+    /// so that the heap gives up the smallest value first.
+    heads: BinaryHeap<Reverse<(T, usize)>>,
 
-    /// The value to be returned upon a call to next()
-    next_value: T,
+    /// Whether the next values have been pulled from the sets yet.
+    started: bool,
 }
 
-impl<T: Ord> InfiniteUnion<T> {
+impl<T: Ord + 'static> InfiniteUnion<T> {
+    /// Creates the union of two sets. See `from_many`.
     pub fn from_sets(
-        mut first_set: impl InfiniteSet<Item = T> + 'static,
-        mut second_set: impl InfiniteSet<Item = T> + 'static,
+        first_set: impl InfiniteSet<Item = T> + 'static,
+        second_set: impl InfiniteSet<Item = T> + 'static,
     ) -> Self {
-        let mut first_next = first_set
-            .next()
-            .expect("first infinite set in union didn't have a next value");
-
-        let mut second_next = loop {
-            let n = second_set
-                .next()
-                .expect("second infinite set in union didn't have a next value");
-            if n != first_next {
-                break n;
-            }
-        };
+        Self::from_boxed(vec![Box::new(first_set), Box::new(second_set)])
+    }
 
-        // get the next value by finding the lesser of the two next values from the infinite set
-        let next_value = loop {
-            match first_next.cmp(&second_next) {
-                Ordering::Less => {
-                    // if the first next value is lesser, then use it and advance the first set
-                    let tmp = first_next;
-                    first_next = first_set
-                        .next()
-                        .expect("first infinite set in union didn't have a next value");
-                    break tmp;
-                }
-                Ordering::Equal => {
-                    // if both of the next values are equal, advance the first set.
-                    first_next = first_set
-                        .next()
-                        .expect("first infinite set in union didn't have a next value");
+    /// Creates the union of any number of sets, without nesting a union for every set. Sets of
+    /// different types can be combined by boxing them as `Box<dyn InfiniteSet<Item = T>>`.
+    ///
+    /// If every set is periodic, the union iterates a single PeriodicSet of their combined
+    /// residues instead of merging them.
+    pub fn from_many<S>(sets: impl IntoIterator<Item = S>) -> Self
+    where
+        S: InfiniteSet<Item = T> + 'static,
+    {
+        Self::from_boxed(
+            sets.into_iter()
+                .map(|set| Box::new(set) as Box<dyn InfiniteSet<Item = T>>)
+                .collect(),
+        )
+    }
 
-                    // continue looking for a lesser value
-                    continue;
-                }
-                Ordering::Greater => {
-                    // if the first next value is greater, then use the second value and advance the second set
-                    let tmp = second_next;
-                    second_next = second_set
-                        .next()
-                        .expect("second infinite set in union didn't have a next value");
-                    break tmp;
-                }
-            }
+    fn from_boxed(sets: Vec<Box<dyn InfiniteSet<Item = T>>>) -> Self {
+        let collapsed = collapse(sets.iter().map(|set| set.as_periodic()), |a, b| {
+            a.collapse_union(b)
+        });
+        let sets = match collapsed {
+            Some(union) => vec![Box::new(union) as Box<dyn InfiniteSet<Item = T>>],
+            None => sets,
         };
 
         Self {
-            first_set: Box::new(first_set),
-            second_set: Box::new(second_set),
-            first_next,
-            second_next,
-            next_value,
+            sets,
+            heads: BinaryHeap::new(),
+            started: false,
+        }
+    }
+}
+
+impl<T: Ord> InfiniteUnion<T> {
+    /// Advances the set at `index`, storing its next value unless it has ended.
+    fn advance(&mut self, index: usize) {
+        if let Some(x) = self.sets[index].next() {
+            self.heads.push(Reverse((x, index)));
+        }
+    }
+
+    /// Pulls the first value from every set, the first time the union is iterated.
+    fn start(&mut self) {
+        if !self.started {
+            (0..self.sets.len()).for_each(|i| self.advance(i));
+            self.started = true;
         }
     }
 }
 
-impl<T: Ord + Clone> InfiniteSet for InfiniteUnion<T> {
+impl<T: Ord> InfiniteSet for InfiniteUnion<T> {
     fn contains(&self, x: &T) -> bool {
-        self.first_set.contains(x) || self.second_set.contains(x)
+        self.sets.iter().any(|set| set.contains(x))
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        // a union of no sets is empty
+        let empty = SetFacts {
+            empty: true,
+            ..SetFacts::default()
+        };
+
+        self.sets
+            .iter()
+            .map(|set| set.facts())
+            .fold(empty, SetFacts::union)
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.sets.iter().find_map(|set| set.overflow())
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        // a union that hasn't started can have every set seek the bound straight away
+        if !self.started {
+            for (i, set) in self.sets.iter_mut().enumerate() {
+                if let Some(x) = set.seek(lower_bound) {
+                    self.heads.push(Reverse((x, i)));
+                }
+            }
+            self.started = true;
+        }
+
+        // move every set whose next value is behind the bound up to it
+        while let Some(Reverse((next, _))) = self.heads.peek() {
+            if next >= lower_bound {
+                break;
+            }
+
+            let Reverse((_, index)) = self.heads.pop().expect("the heap was just peeked");
+            if let Some(x) = self.sets[index].seek(lower_bound) {
+                self.heads.push(Reverse((x, index)));
+            }
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        let density: f64 = self.sets.iter().map(|set| set.estimated_density()).sum();
+        density.min(1.0)
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        // the stored next values haven't been yielded yet, so each set resumes from its own
+        let periodic = self.sets.iter().enumerate().map(|(i, set)| {
+            if !self.started {
+                return set.as_periodic();
+            }
+
+            let next = self
+                .heads
+                .iter()
+                .find(|Reverse((_, index))| *index == i)
+                .map(|Reverse((x, _))| x);
+            set.as_periodic()?.resumed_at(next)
+        });
+
+        collapse(periodic, |a, b| a.collapse_union(b))
     }
 }
 
-impl<T: Ord + Clone> Iterator for InfiniteUnion<T> {
+impl<T: Ord> Iterator for InfiniteUnion<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let tmp = self.next_value.clone();
-
-        self.next_value = loop {
-            match self.first_next.cmp(&self.second_next) {
-                Ordering::Less => {
-                    // if the first next value is lesser, then use it and advance the first set
-                    let tmp = self.first_next.clone();
-                    self.first_next = self
-                        .first_set
-                        .next()
-                        .expect("first infinite set in union didn't have a next value");
-                    break tmp;
+        self.start();
+
+        // take the smallest next value, advancing the set it came from
+        let Reverse((x, index)) = self.heads.pop()?;
+        self.advance(index);
+
+        // other sets with the same value have their copy dropped
+        while let Some(Reverse((next, _))) = self.heads.peek() {
+            if *next != x {
+                break;
+            }
+
+            let Reverse((_, index)) = self.heads.pop().expect("the heap was just peeked");
+            self.advance(index);
+        }
+
+        Some(x)
+    }
+}
+
+/// Collapses periodic sets into one with `combine`, or returns None if any of the sets isn't
+/// periodic or any pair can't be combined.
+fn collapse<T>(
+    mut sets: impl Iterator<Item = Option<PeriodicSet<T>>>,
+    combine: impl Fn(&PeriodicSet<T>, &PeriodicSet<T>) -> Option<PeriodicSet<T>>,
+) -> Option<PeriodicSet<T>> {
+    let first = sets.next()??;
+    sets.try_fold(first, |collapsed, set| combine(&collapsed, &set?))
+}
+
+/// Takes the value that comes first out of two stored next values, where `first_comes` is how the
+/// value that comes first compares to the other (Less when merging ascending, Greater when
+/// merging descending). The taken value is replaced by advancing its set, and a value stored by
+/// both sets, or repeated by one of them, is only taken once. A set that has ended is skipped.
+pub(crate) fn take_merged<T: Ord>(
+    first_next: &mut Option<T>,
+    second_next: &mut Option<T>,
+    mut advance_first: impl FnMut() -> Option<T>,
+    mut advance_second: impl FnMut() -> Option<T>,
+    first_comes: Ordering,
+) -> Option<T> {
+    let use_first = match (&*first_next, &*second_next) {
+        (None, None) => return None,
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (Some(a), Some(b)) => a.cmp(b) != first_comes.reverse(),
+    };
+    let x = if use_first {
+        std::mem::replace(first_next, advance_first())?
+    } else {
+        std::mem::replace(second_next, advance_second())?
+    };
+
+    // drop every other copy of the value, whether the other set has it too or a set repeats it
+    // (like the 1, 1 that a recurrence can start with)
+    while first_next.as_ref() == Some(&x) {
+        *first_next = advance_first();
+    }
+    while second_next.as_ref() == Some(&x) {
+        *second_next = advance_second();
+    }
+
+    Some(x)
+}
+
+/// A intersection of any number of infinite sets. InfiniteIntersection is also an InfiniteSet.
+///
+/// The intersection is iterated with a leapfrog join: the set with the largest next value sets
+/// the bound, every other set is moved up to its first value not below the bound, and this
+/// repeats until all of the sets agree on a value.
+///
+/// When the facts of the sets prove them disjoint (see `InfiniteSet::facts`), the intersection
+/// is known to be empty as soon as it is created and next() returns None without iterating.
+///
+/// WARNING: an empty intersection that can't be proven empty from the facts will stall the
+/// program when calling next(), unless a search budget has been set with `with_budget`. Use
+/// `try_next_within` to search a bounded number of candidates instead. An intersection with a
+/// finite set, such as a FiniteSet, can't stall, since it ends as soon as any of its sets does.
+pub struct InfiniteIntersection<T> {
+    sets: Vec<Box<dyn InfiniteSet<Item = T>>>,
+
+    /// The value each set was last moved to, or None if the set has to be advanced before the
+    /// search goes on (as it does once its value has been yielded).
+    heads: Vec<Option<T>>,
+
+    /// The indices of the sets from the sparsest to the densest, by their estimated density when
+    /// the intersection was created. Sets without a value are advanced in this order.
+    order: Vec<usize>,
+
+    /// Set when the facts of the sets prove that they have no elements in common.
+    known_empty: bool,
+
+    /// The most candidates next() will check before giving up, if any.
+    budget: Option<usize>,
+}
+
+impl<T: Ord + 'static> InfiniteIntersection<T> {
+    /// Creates the intersection of two sets. See `from_many`.
+    pub fn from_sets<I, J>(first: I, second: J) -> Self
+    where
+        I: InfiniteSet<Item = T> + 'static,
+        J: InfiniteSet<Item = T> + 'static,
+    {
+        Self::from_boxed(vec![Box::new(first), Box::new(second)])
+    }
+
+    /// Creates the intersection of any number of sets, without nesting an intersection for every
+    /// set. Sets of different types can be combined by boxing them as
+    /// `Box<dyn InfiniteSet<Item = T>>`.
+    ///
+    /// If every set is periodic, the intersection iterates a single PeriodicSet of their common
+    /// residues instead of searching the sets. The intersection of no sets is empty.
+    pub fn from_many<S>(sets: impl IntoIterator<Item = S>) -> Self
+    where
+        S: InfiniteSet<Item = T> + 'static,
+    {
+        Self::from_boxed(
+            sets.into_iter()
+                .map(|set| Box::new(set) as Box<dyn InfiniteSet<Item = T>>)
+                .collect(),
+        )
+    }
+
+    fn from_boxed(sets: Vec<Box<dyn InfiniteSet<Item = T>>>) -> Self {
+        let known_empty = intersect_facts(&sets).empty;
+
+        let collapsed = collapse(sets.iter().map(|set| set.as_periodic()), |a, b| {
+            a.collapse_intersection(b)
+        });
+        let sets = match collapsed {
+            Some(intersection) => vec![Box::new(intersection) as Box<dyn InfiniteSet<Item = T>>],
+            None => match intersect_closed_form(&sets) {
+                Some(intersection) => vec![intersection],
+                None => sets,
+            },
+        };
+
+        let densities: Vec<f64> = sets.iter().map(|set| set.estimated_density()).collect();
+        let mut order: Vec<usize> = (0..sets.len()).collect();
+        order.sort_by(|a, b| densities[*a].total_cmp(&densities[*b]));
+
+        Self {
+            known_empty: known_empty || intersect_facts(&sets).empty,
+            heads: sets.iter().map(|_| None).collect(),
+            order,
+            sets,
+            budget: None,
+        }
+    }
+}
+
+impl<T: Ord> InfiniteIntersection<T> {
+    /// Returns true if the intersection was proven empty from the facts of its sets.
+    pub fn is_known_empty(&self) -> bool {
+        self.known_empty
+    }
+
+    /// Limits every call to next() to checking at most `limit` candidates. If no element is
+    /// found within the budget, next() returns None instead of searching forever.
+    pub fn with_budget(mut self, limit: usize) -> Self {
+        self.budget = Some(limit);
+        self
+    }
+
+    /// Finds the next element of the intersection, checking at most `limit` candidates. A
+    /// candidate is a value that one of the sets has and the others have to be moved up to.
+    /// Rejected candidates are consumed, so calling this again resumes the search where it left
+    /// off.
+    ///
+    /// Returns Ok(None) if the intersection has ended, which is immediately the case for a
+    /// known-empty intersection, or if any of its sets has ended (for example by overflowing).
+    pub fn try_next_within(&mut self, limit: usize) -> Result<Option<T>, SearchExhausted> {
+        if self.known_empty {
+            return Ok(None);
+        }
+
+        for _ in 0..limit {
+            // the set with the largest value so far, which is kept up to date as the other sets
+            // move rather than found again for each of them
+            let mut leader = (0..self.heads.len())
+                .max_by(|a, b| self.heads[*a].cmp(&self.heads[*b]))
+                .filter(|i| self.heads[*i].is_some());
+
+            // every set needs a value to take part. the sparsest set without one advances on its
+            // own, and the others leap straight to the largest value so far, so that dense sets
+            // are never walked through
+            for &i in &self.order {
+                if self.heads[i].is_some() {
+                    continue;
                 }
-                Ordering::Equal => {
-                    // if both of the next values are equal, advance the first set.
-                    self.first_next = self
-                        .first_set
-                        .next()
-                        .expect("first infinite set in union didn't have a next value");
 
-                    // continue looking for a lesser value
+                let bound = match leader {
+                    Some(leader) => self.heads[leader].as_ref(),
+                    None => None,
+                };
+                let next = match bound {
+                    Some(bound) => self.sets[i].seek(bound),
+                    None => self.sets[i].next(),
+                };
+                let x = match next {
+                    Some(x) => x,
+                    None => return Ok(None),
+                };
+
+                if bound.is_none_or(|bound| x > *bound) {
+                    leader = Some(i);
+                }
+                self.heads[i] = Some(x);
+            }
+
+            // the largest value is the candidate, and every other set leaps up to it
+            let leader = leader.expect("there is at least one set");
+            let candidate = self.heads[leader].take().expect("every set has a value");
+
+            let mut agreed = true;
+            for (i, (set, head)) in self.sets.iter_mut().zip(self.heads.iter_mut()).enumerate() {
+                if i == leader {
                     continue;
                 }
-                Ordering::Greater => {
-                    // if the first next value is greater, then use the second value and advance the second set
-                    let tmp = self.second_next.clone();
-                    self.second_next = self
-                        .second_set
-                        .next()
-                        .expect("second infinite set in union didn't have a next value");
-                    break tmp;
+
+                if head.as_ref().is_some_and(|x| *x < candidate) {
+                    *head = set.seek(&candidate);
+                    if head.is_none() {
+                        return Ok(None);
+                    }
                 }
+                agreed &= head.as_ref() == Some(&candidate);
             }
-        };
 
-        Some(tmp)
+            if agreed {
+                // every set has yielded the candidate, so they all move on next time
+                self.heads.iter_mut().for_each(|head| *head = None);
+                return Ok(Some(candidate));
+            }
+            self.heads[leader] = Some(candidate);
+        }
+
+        Err(SearchExhausted { candidates: limit })
     }
 }
 
-/// A intersection between two infinite sets. InfiniteIntersection is also an InfiniteSet.
+/// Intersects the sets with `InfiniteSet::intersect_closed_form`, or returns None if there is only
+/// one set or any of them can't be intersected that way.
+fn intersect_closed_form<T: 'static>(
+    sets: &[Box<dyn InfiniteSet<Item = T>>],
+) -> Option<Box<dyn InfiniteSet<Item = T>>> {
+    let (first, rest) = sets.split_first()?;
+    let (second, rest) = rest.split_first()?;
+
+    rest.iter()
+        .try_fold(first.intersect_closed_form(&**second)?, |both, set| {
+            both.intersect_closed_form(&**set)
+        })
+}
+
+fn intersect_facts<T: Ord>(sets: &[Box<dyn InfiniteSet<Item = T>>]) -> SetFacts<T> {
+    let mut facts = sets.iter().map(|set| set.facts());
+    let first = match facts.next() {
+        Some(first) => first,
+        // there is nothing to intersect, so nothing is in the intersection
+        None => {
+            return SetFacts {
+                empty: true,
+                ..SetFacts::default()
+            }
+        }
+    };
+    facts.fold(first, SetFacts::intersect)
+}
+
+impl<T: Ord> InfiniteSet for InfiniteIntersection<T> {
+    fn contains(&self, x: &<Self as Iterator>::Item) -> bool {
+        !self.known_empty && self.sets.iter().all(|set| set.contains(x))
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        intersect_facts(&self.sets)
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.sets.iter().find_map(|set| set.overflow())
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        if self.known_empty {
+            return None;
+        }
+
+        // start the search with every set at the bound or past it
+        for (set, head) in self.sets.iter_mut().zip(self.heads.iter_mut()) {
+            if head.as_ref().is_none_or(|x| x < lower_bound) {
+                *head = Some(set.seek(lower_bound)?);
+            }
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        if self.known_empty {
+            return 0.0;
+        }
+
+        // as if the sets were independent of each other
+        self.sets
+            .iter()
+            .map(|set| set.estimated_density())
+            .product()
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        // a set that has a value stored resumes from it, since it hasn't been yielded yet
+        let periodic = self.sets.iter().zip(&self.heads).map(|(set, head)| {
+            let periodic = set.as_periodic()?;
+            match head {
+                Some(x) => periodic.resumed_at(Some(x)),
+                None => Some(periodic),
+            }
+        });
+
+        collapse(periodic, |a, b| a.collapse_intersection(b))
+    }
+}
+
+impl<T: Ord> Iterator for InfiniteIntersection<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.budget {
+            Some(limit) => self.try_next_within(limit).ok().flatten(),
+            // without a budget the search goes on until it finds an element or a set ends
+            None => loop {
+                if let Ok(next) = self.try_next_within(usize::MAX) {
+                    break next;
+                }
+            },
+        }
+    }
+}
+
+/// A difference between two infinite sets: every element of the first set that is not in the
+/// second. InfiniteDifference is also an InfiniteSet.
 ///
-/// first_next and second_next are the stored next values in the iterators. We store them because
-/// simply comparing the results of next() on each set could unfairly throw away a value from one
-/// of the sets and exclude the value from the union.
+/// Only the first set is iterated; the second is only ever asked whether it contains a value, so
+/// it is never advanced.
 ///
-/// WARNING: InfiniteIntersection currently does not check for empty intersections. Calling next()
-/// on an empty intersection will stall the program!
-pub struct InfiniteIntersection<T> {
+/// WARNING: like InfiniteIntersection, InfiniteDifference does not check for empty differences.
+/// Calling next() when the second set contains the rest of the first will stall the program,
+/// unless a search budget has been set with `with_budget`.
+pub struct InfiniteDifference<T> {
     first: Box<dyn InfiniteSet<Item = T>>,
     second: Box<dyn InfiniteSet<Item = T>>,
+
+    /// The most candidates next() will check before giving up, if any.
+    budget: Option<usize>,
 }
 
-impl<T> InfiniteIntersection<T> {
+impl<T: 'static> InfiniteDifference<T> {
+    /// Creates the difference of two sets. If both sets are periodic, the difference iterates a
+    /// single PeriodicSet of the residues only the first set has instead of filtering it.
     pub fn from_sets<I, J>(first: I, second: J) -> Self
     where
         I: InfiniteSet<Item = T> + 'static,
         J: InfiniteSet<Item = T> + 'static,
     {
+        let collapsed = first
+            .as_periodic()
+            .zip(second.as_periodic())
+            .and_then(|(first, second)| first.collapse_difference(&second));
+
+        // no element of the collapsed set is in the second set, so the second set can stay as
+        // it is
+        let first: Box<dyn InfiniteSet<Item = T>> = match collapsed {
+            Some(difference) => Box::new(difference),
+            None => Box::new(first),
+        };
+
         Self {
-            first: Box::new(first),
+            first,
             second: Box::new(second),
+            budget: None,
+        }
+    }
+}
+
+impl<T> InfiniteDifference<T> {
+    /// Limits every call to next() to checking at most `limit` candidates from the first set.
+    /// If no element is found within the budget, next() returns None instead of searching
+    /// forever.
+    pub fn with_budget(mut self, limit: usize) -> Self {
+        self.budget = Some(limit);
+        self
+    }
+
+    /// Finds the next element of the difference, checking at most `limit` candidates from the
+    /// first set. Rejected candidates are consumed, so calling this again resumes the search
+    /// where it left off.
+    ///
+    /// Returns Ok(None) if the difference has ended because the first set has ended (for example
+    /// by overflowing).
+    pub fn try_next_within(&mut self, limit: usize) -> Result<Option<T>, SearchExhausted> {
+        for _ in 0..limit {
+            let x = match self.first.next() {
+                Some(x) => x,
+                None => return Ok(None),
+            };
+            if !self.second.contains(&x) {
+                return Ok(Some(x));
+            }
         }
+
+        Err(SearchExhausted { candidates: limit })
     }
 }
 
-impl<T> InfiniteSet for InfiniteIntersection<T> {
+impl<T> InfiniteSet for InfiniteDifference<T> {
     fn contains(&self, x: &<Self as Iterator>::Item) -> bool {
-        self.first.contains(x) && self.second.contains(x)
+        self.first.contains(x) && !self.second.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        // a difference is a subset of its first set
+        self.first.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first.overflow().or_else(|| self.second.overflow())
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T>
+    where
+        T: Ord,
+    {
+        // jump the first set to the bound, then carry on as usual if it landed on a value the
+        // second set has
+        let x = self.first.seek(lower_bound)?;
+        if !self.second.contains(&x) {
+            return Some(x);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.first.estimated_density() * (1.0 - self.second.estimated_density())
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        self.first
+            .as_periodic()?
+            .collapse_difference(&self.second.as_periodic()?)
     }
 }
 
-impl<T> Iterator for InfiniteIntersection<T> {
+impl<T> Iterator for InfiniteDifference<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // we find the next value by advancing the first set until its value can also be found in
-        // the second set
+        if let Some(limit) = self.budget {
+            return self.try_next_within(limit).ok().flatten();
+        }
+
+        // advance the first set until it yields a value the second set doesn't have. if the
+        // first set ends, so does the difference
         let next = loop {
-            let x = self
-                .first
-                .next()
-                .expect("first infinite set in intersection didn't have a next value");
-            if self.second.contains(&x) {
+            let x = self.first.next()?;
+            if !self.second.contains(&x) {
                 break x;
-            } else {
-                // not needed obviously, but is a reminder that we'll continue looping if the
-                // element from the first set isn't also in the second
-                continue;
             }
         };
 
         Some(next)
     }
 }
+
+/// A symmetric difference between two infinite sets: every element that is in exactly one of
+/// them. InfiniteSymmetricDifference is also an InfiniteSet.
+///
+/// Like InfiniteUnion, both sets are merged in ascending order through their stored next values,
+/// except that a value showing up in both sets is dropped instead of yielded once. A value that a
+/// set repeats counts once, so it is still dropped if the other set has it too. A stored value
+/// of None means that its set has ended, and the rest of the other set is yielded as-is. Like
+/// InfiniteUnion, nothing is pulled from the sets until the symmetric difference is first
+/// iterated.
+///
+/// WARNING: InfiniteSymmetricDifference does not check for sets that agree from some point on.
+/// Calling next() on one that has run out of disagreements will stall the program!
+pub struct InfiniteSymmetricDifference<T>
+where
+    T: Ord,
+{
+    first_set: Box<dyn InfiniteSet<Item = T>>,
+    second_set: Box<dyn InfiniteSet<Item = T>>,
+
+    first_next: Option<T>,
+    second_next: Option<T>,
+
+    /// Whether the next values have been pulled from the sets yet.
+    started: bool,
+}
+
+impl<T: Ord> InfiniteSymmetricDifference<T> {
+    /// Creates the symmetric difference of two sets. Nothing is pulled from either set until the
+    /// symmetric difference is first iterated.
+    pub fn from_sets(
+        first_set: impl InfiniteSet<Item = T> + 'static,
+        second_set: impl InfiniteSet<Item = T> + 'static,
+    ) -> Self {
+        Self {
+            first_set: Box::new(first_set),
+            second_set: Box::new(second_set),
+            first_next: None,
+            second_next: None,
+            started: false,
+        }
+    }
+
+    /// Takes the stored next value of the first set, skipping any repeats of it that follow so
+    /// that a set yielding a value twice still only has it once.
+    fn advance_first(&mut self) -> Option<T> {
+        let next = self.first_set.next();
+        let current = std::mem::replace(&mut self.first_next, next);
+        while current.is_some() && self.first_next == current {
+            self.first_next = self.first_set.next();
+        }
+
+        current
+    }
+
+    /// Takes the stored next value of the second set, skipping any repeats of it that follow.
+    fn advance_second(&mut self) -> Option<T> {
+        let next = self.second_set.next();
+        let current = std::mem::replace(&mut self.second_next, next);
+        while current.is_some() && self.second_next == current {
+            self.second_next = self.second_set.next();
+        }
+
+        current
+    }
+}
+
+impl<T: Ord> InfiniteSet for InfiniteSymmetricDifference<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.first_set.contains(x) != self.second_set.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        // a symmetric difference is a subset of the union
+        self.first_set.facts().union(self.second_set.facts())
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first_set
+            .overflow()
+            .or_else(|| self.second_set.overflow())
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        // move each set whose next value is behind the bound up to it. before the symmetric
+        // difference has been iterated, both sets can seek the bound directly
+        if !self.started {
+            self.first_next = self.first_set.seek(lower_bound);
+            self.second_next = self.second_set.seek(lower_bound);
+            self.started = true;
+        }
+        if self.first_next.as_ref().is_some_and(|x| x < lower_bound) {
+            self.first_next = self.first_set.seek(lower_bound);
+        }
+        if self.second_next.as_ref().is_some_and(|x| x < lower_bound) {
+            self.second_next = self.second_set.seek(lower_bound);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        let first = self.first_set.estimated_density();
+        let second = self.second_set.estimated_density();
+
+        first + second - 2.0 * first * second
+    }
+}
+
+impl<T: Ord> Iterator for InfiniteSymmetricDifference<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.first_next = self.first_set.next();
+            self.second_next = self.second_set.next();
+            self.started = true;
+        }
+
+        loop {
+            let ordering = match (&self.first_next, &self.second_next) {
+                (None, None) => return None,
+                // once a set has ended, nothing else can be shared with it
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(a), Some(b)) => a.cmp(b),
+            };
+
+            match ordering {
+                // the lesser of the two next values can't be in the other set, since the other
+                // set has already moved past it
+                Ordering::Less => return self.advance_first(),
+                Ordering::Greater => return self.advance_second(),
+                Ordering::Equal => {
+                    // the value is in both sets, so skip it in both
+                    self.advance_first();
+                    self.advance_second();
+                }
+            }
+        }
+    }
+}
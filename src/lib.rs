@@ -0,0 +1,67 @@
+//! Lazily-evaluated infinite sets.
+//!
+//! An [`InfiniteSet`] is an ascending [`Iterator`] paired with a membership test, so sets can be
+//! combined with [`InfiniteSet::union`] and [`InfiniteSet::intersect`] without ever materializing
+//! them. Sets that go on forever in both directions, such as all of the integers, implement the
+//! companion [`BidirectionalSet`] trait instead. Concrete sets live in the [`sets`] module, and
+//! everything commonly needed is re-exported from [`prelude`].
+//!
+//! The built-in sets are generic over their [`Element`] type. Enabling the `bigint` feature adds
+//! `BigUint`, an arbitrary-precision element type for sets that would overflow any primitive
+//! integer.
+
+mod bidirectional;
+#[cfg(feature = "bigint")]
+mod bigint;
+mod composed;
+mod element;
+mod error;
+mod facts;
+mod figurate;
+mod finite;
+mod infinite_set;
+mod modular;
+mod overflow;
+mod periodic;
+mod primes;
+mod product;
+mod roots;
+pub mod sets;
+
+pub use bidirectional::{
+    BidirectionalIntersection, BidirectionalSet, BidirectionalUnion, Interleaved,
+};
+#[cfg(feature = "bigint")]
+pub use bigint::{BigUint, ParseBigUintError};
+pub use composed::{Intersection, MonotoneImage, PredicateSet, Union};
+pub use element::Element;
+pub use error::{OverflowError, SearchExhausted};
+pub use facts::{Residue, SetFacts};
+pub use infinite_set::{
+    InfiniteDifference, InfiniteIntersection, InfiniteSet, InfiniteSymmetricDifference,
+    InfiniteUnion,
+};
+pub use overflow::OverflowPolicy;
+pub use product::{InfiniteProduct, Pair};
+
+/// Glob-importable re-exports of the trait, its combinators and the built-in sets.
+pub mod prelude {
+    pub use crate::bidirectional::{
+        BidirectionalIntersection, BidirectionalSet, BidirectionalUnion,
+    };
+    pub use crate::composed::{Intersection, MonotoneImage, PredicateSet, Union};
+    pub use crate::infinite_set::{
+        InfiniteDifference, InfiniteIntersection, InfiniteSet, InfiniteSymmetricDifference,
+        InfiniteUnion,
+    };
+    pub use crate::overflow::OverflowPolicy;
+    pub use crate::product::{InfiniteProduct, Pair};
+    pub use crate::sets::{
+        ArithmeticProgression, EmptySet, FiniteRange, FiniteSet, InfiniteCubes, InfiniteEvens,
+        InfiniteFactorials, InfiniteFibonacci, InfiniteIntegers, InfiniteKthPowers,
+        InfiniteNegativeOdds, InfiniteOdds, InfinitePolygonalNumbers, InfinitePositiveInts,
+        InfinitePowers, InfinitePrimes, InfinitePyramidalNumbers, InfiniteSignedEvens,
+        InfiniteSignedOdds, InfiniteSquares, InfiniteTriangularNumbers, InfiniteTwoPowers,
+        LinearRecurrence, PeriodicSet,
+    };
+}
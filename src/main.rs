@@ -1,12 +1,9 @@
-mod infinite_set;
-mod sets;
-
-use infinite_set::InfiniteSet;
+use infinite_sets::prelude::*;
 
 fn main() {
     {
-        let primes = sets::InfinitePrimes::new();
-        let odds = sets::InfiniteOdds::new();
+        let primes = InfinitePrimes::new();
+        let odds = InfiniteOdds::new();
 
         let intersection: Vec<u128> = primes.intersect(odds).take(10).collect();
 
@@ -14,28 +11,41 @@ fn main() {
     }
 
     {
-        let evens = sets::InfiniteEvens::new();
-        let odds = sets::InfiniteOdds::new();
+        let evens = InfiniteEvens::new();
+        let odds = InfiniteOdds::new();
 
         let union: Vec<u128> = evens.union(odds).take(10).collect();
 
         println!("evens union odds: {:?}", union);
     }
 
-    // {
-    //     let evens = sets::InfiniteEvens::new();
-    //     let odds = sets::InfiniteOdds::new();
+    {
+        let evens = InfiniteEvens::new();
+        let odds = InfiniteOdds::new();
+
+        // evens and odds never intersect, which their residues mod 2 prove before we iterate
+        let never_intersect: Vec<u128> = evens.intersect(odds).take(10).collect();
+
+        println!("evens intersection odds: {:?}", never_intersect);
+    }
 
-    //     // we would expect this operation to last forever
-    //     // i tested it; and it does!
-    //     let never_intersect: Vec<u128> = evens.intersect(odds).take(10).collect();
+    {
+        // sets can be made of any integer type, not just u128
+        let evens = InfiniteEvens::<u32>::new();
+        let primes = InfinitePrimes::new();
+
+        // nothing proves that evens and primes only share 2, so an unbounded search for a second
+        // element would last forever. bounding the search lets us give up instead
+        let mut even_primes = evens.intersect(primes);
+        let first = even_primes.try_next_within(1000);
+        let second = even_primes.try_next_within(1000);
+
+        println!("evens intersection primes: {:?}, then {:?}", first, second);
+    }
 
-    //     println!("{:?}", never_intersect);
-    // }
-    
     {
-        let powers_of_two = sets::InfiniteTwoPowers::new();
-        let odds = sets::InfiniteOdds::new();
+        let powers_of_two = InfiniteTwoPowers::new();
+        let odds = InfiniteOdds::new();
 
         let union: Vec<u128> = powers_of_two.union(odds).take(30).collect();
 
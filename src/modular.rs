@@ -0,0 +1,121 @@
+//! Modular arithmetic over any Element, written so that no intermediate value is larger than the
+//! modulus. That keeps it from overflowing fixed-width types even for moduli close to their
+//! largest value.
+
+use crate::element::Element;
+
+/// Returns (a + b) mod n, for a and b already reduced modulo n.
+pub(crate) fn add_mod<T: Element>(a: &T, b: &T, n: &T) -> T {
+    let room = n.clone() - b.clone();
+    if *a >= room {
+        a.clone() - room
+    } else {
+        a.clone() + b.clone()
+    }
+}
+
+/// Returns (a - b) mod n, for a and b already reduced modulo n.
+pub(crate) fn sub_mod<T: Element>(a: &T, b: &T, n: &T) -> T {
+    if a >= b {
+        a.clone() - b.clone()
+    } else {
+        n.clone() - (b.clone() - a.clone())
+    }
+}
+
+/// Returns (a * b) mod n, for a and b already reduced modulo n.
+pub(crate) fn mul_mod<T: Element>(a: &T, b: &T, n: &T) -> T {
+    if let Some(product) = a.checked_mul(b) {
+        return product % n.clone();
+    }
+
+    // the product doesn't fit, so build it up one bit of b at a time
+    let mut result = T::zero();
+    let mut a = a.clone();
+    let mut b = b.clone();
+    while !b.is_zero() {
+        if b.is_odd() {
+            result = add_mod(&result, &a, n);
+        }
+        a = add_mod(&a, &a, n);
+        b = b / T::two();
+    }
+
+    result
+}
+
+/// Returns the inverse of `a` modulo `n`, if `a` and `n` are coprime.
+pub(crate) fn inverse_mod<T: Element>(a: &T, n: &T) -> Option<T> {
+    if n.is_one() {
+        return Some(T::zero());
+    }
+
+    // the extended Euclidean algorithm, keeping the coefficients reduced modulo n so that they
+    // never go negative
+    let (mut old_r, mut r) = (a.mod_floor(n), n.clone());
+    let (mut old_s, mut s) = (T::one(), T::zero());
+    while !r.is_zero() {
+        let (q, next_r) = old_r.div_rem(&r);
+        old_r = std::mem::replace(&mut r, next_r);
+
+        let next_s = sub_mod(&old_s, &mul_mod(&q.mod_floor(n), &s, n), n);
+        old_s = std::mem::replace(&mut s, next_s);
+    }
+
+    if old_r.is_one() {
+        Some(old_s)
+    } else {
+        None
+    }
+}
+
+/// Returns lcm(m, n), or None if it doesn't fit in T.
+pub(crate) fn checked_lcm<T: Element>(m: &T, n: &T) -> Option<T> {
+    (m.clone() / m.gcd(n)).checked_mul(n)
+}
+
+/// Solves x = a (mod m) and x = b (mod n) with the Chinese Remainder Theorem, for a and b
+/// already reduced modulo m and n. Returns the solution x modulo lcm(m, n), or None if there is
+/// no solution.
+///
+/// lcm(m, n) must fit in T (see `checked_lcm`), which keeps the solution from overflowing too.
+pub(crate) fn crt<T: Element>(a: &T, m: &T, b: &T, n: &T) -> Option<T> {
+    let g = m.gcd(n);
+
+    // there is a solution exactly when a and b agree modulo the gcd
+    let difference = if b >= a {
+        b.clone() - a.clone()
+    } else {
+        a.clone() - b.clone()
+    };
+    if !difference.is_multiple_of(&g) {
+        return None;
+    }
+
+    // find k with a + m * k = b (mod n), so that a + m * k is the solution
+    let n_reduced = n.clone() / g.clone();
+    let quotient = (difference / g.clone()).mod_floor(&n_reduced);
+    let target = if b >= a {
+        quotient
+    } else {
+        sub_mod(&T::zero(), &quotient, &n_reduced)
+    };
+    let inverse = inverse_mod(&(m.clone() / g).mod_floor(&n_reduced), &n_reduced)
+        .expect("m / gcd(m, n) and n / gcd(m, n) are coprime");
+    let k = mul_mod(&target, &inverse, &n_reduced);
+
+    Some(a.clone() + m.clone() * k)
+}
+
+/// Returns the first term of the progression start, start + step, start + 2 * step, ... that is
+/// at least `bound`, or None if it doesn't fit in T.
+pub(crate) fn first_at_least<T: Element>(start: &T, step: &T, bound: &T) -> Option<T> {
+    if bound <= start {
+        return Some(start.clone());
+    }
+
+    // the gap up to the next term is found modulo the step, which can't overflow the way
+    // bound - start can for signed types
+    let offset = sub_mod(&start.mod_floor(step), &bound.mod_floor(step), step);
+    bound.checked_add(&offset)
+}
@@ -0,0 +1,60 @@
+use std::any::type_name;
+
+use crate::error::OverflowError;
+
+/// What a set made of a fixed-width element type does once its next element would not fit in
+/// that type.
+///
+/// There is deliberately no saturating policy: repeating or clamping to the largest value of the
+/// type would yield elements that either aren't in the set or break its ascending order, which
+/// the combinators rely on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// End the iteration, and report the OverflowError through `InfiniteSet::overflow`.
+    #[default]
+    Stop,
+
+    /// Panic with the OverflowError as the message.
+    Panic,
+}
+
+/// Applies a set's OverflowPolicy and remembers whether the set has overflowed.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct OverflowGuard {
+    policy: OverflowPolicy,
+    error: Option<OverflowError>,
+}
+
+impl OverflowGuard {
+    pub(crate) fn new(policy: OverflowPolicy) -> Self {
+        Self {
+            policy,
+            error: None,
+        }
+    }
+
+    pub(crate) fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    pub(crate) fn error(&self) -> Option<OverflowError> {
+        self.error
+    }
+
+    /// Called when the set named `set` couldn't produce its next element because it wouldn't fit
+    /// in `T`. Applies the policy, and returns None so that the set's iterator can end.
+    pub(crate) fn overflowed<T>(&mut self, set: &'static str) -> Option<T> {
+        let error = OverflowError {
+            set,
+            element: type_name::<T>(),
+        };
+
+        match self.policy {
+            OverflowPolicy::Stop => {
+                self.error = Some(error);
+                None
+            }
+            OverflowPolicy::Panic => panic!("{}", error),
+        }
+    }
+}
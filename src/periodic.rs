@@ -0,0 +1,532 @@
+use std::convert::TryFrom;
+
+use num_integer::Integer;
+
+use crate::element::Element;
+use crate::error::OverflowError;
+use crate::facts::{Residue, SetFacts};
+use crate::infinite_set::InfiniteSet;
+use crate::modular;
+use crate::overflow::{OverflowGuard, OverflowPolicy};
+
+/// The most residues that the periodic algebra will build a set from. Past this, the algebra
+/// returns None, and the combinators fall back to combining their operands element by element
+/// rather than spend the memory.
+const COLLAPSE_LIMIT: usize = 1 << 16;
+
+/// Infinite set of the positive integers in a union of residue classes modulo a period, such as
+/// every positive integer that is 1 or 5 mod 6.
+///
+/// Periodic sets are closed under union, intersection, difference and complement, which are
+/// computed in closed form on the residues (see `union_periodic` and friends). The combinators
+/// use this too: when every operand of an `InfiniteUnion`, `InfiniteIntersection` or
+/// `InfiniteDifference` is periodic (see `InfiniteSet::as_periodic`), they iterate one collapsed
+/// PeriodicSet instead of merging or filtering their operands.
+///
+/// The period and residues are u128s, like a `Residue`, so a PeriodicSet of `BigUint` ends at
+/// u128::MAX. `contains` is exact for every element either way. The built-in sets of element
+/// types that go past u128::MAX aren't collapsed, since they would end there too.
+pub struct PeriodicSet<T = u128> {
+    period: u128,
+
+    /// The residues modulo the period that are in the set, in ascending order.
+    residues: Vec<u128>,
+
+    /// The next element to return, or None once the set has no more elements in u128.
+    next: Option<u128>,
+
+    /// The name of the set, for its OverflowError. A PeriodicSet that describes another set (see
+    /// `InfiniteSet::as_periodic`) reports its overflow under that set's name.
+    name: &'static str,
+
+    conversions: Conversions<T>,
+    overflow: OverflowGuard,
+}
+
+/// How a PeriodicSet converts its elements to and from u128. These are captured when the set is
+/// created, where T is known to be an Element, so that the combinators can build periodic sets
+/// of any element type.
+struct Conversions<T> {
+    from_u128: fn(u128) -> Option<T>,
+    to_u128: fn(&T) -> Option<u128>,
+
+    /// Reduces a positive element modulo a period, or returns None for other elements.
+    residue_of: fn(&T, u128) -> Option<u128>,
+}
+
+impl<T> Clone for Conversions<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Conversions<T> {}
+
+fn residue_of<T: Element>(x: &T, period: u128) -> Option<u128> {
+    if *x <= T::zero() {
+        return None;
+    }
+
+    match T::from_u128(period) {
+        Some(period) => x.mod_floor(&period).to_u128(),
+        // x is smaller than a period that doesn't fit in T
+        None => x.to_u128(),
+    }
+}
+
+impl<T: Element> PeriodicSet<T> {
+    /// Creates the set of positive integers congruent to any of `residues` modulo `period`. The
+    /// residues are reduced, so a residue of 0 stands for the positive multiples of the period.
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: u128, residues: impl IntoIterator<Item = u128>) -> Self {
+        assert!(period > 0, "the period of PeriodicSet must be positive");
+
+        let conversions = Conversions {
+            from_u128: T::from_u128,
+            to_u128: |x: &T| x.to_u128(),
+            residue_of: residue_of::<T>,
+        };
+
+        let mut residues: Vec<u128> = residues.into_iter().map(|r| r % period).collect();
+        residues.sort_unstable();
+        residues.dedup();
+
+        Self::from_parts(
+            period,
+            residues,
+            "PeriodicSet",
+            conversions,
+            OverflowGuard::default(),
+        )
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+
+    /// Describes the set named `name`, the positive integers in `residues` modulo `period`, for
+    /// its `InfiniteSet::as_periodic`. The PeriodicSet resumes at the set's `next` element and
+    /// keeps its name and overflow policy, so that it overflows the way the set does.
+    ///
+    /// Returns None if T goes past u128::MAX, where the PeriodicSet would end but the set
+    /// carries on.
+    pub(crate) fn describing(
+        name: &'static str,
+        period: u128,
+        residues: impl IntoIterator<Item = u128>,
+        policy: OverflowPolicy,
+        next: Option<&T>,
+    ) -> Option<Self> {
+        if T::exceeds_u128() {
+            return None;
+        }
+
+        let mut set = Self::new(period, residues).with_overflow_policy(policy);
+        set.name = name;
+        set.resumed_at(next)
+    }
+}
+
+impl<T> PeriodicSet<T> {
+    /// Builds a set starting from its first element, reducing it to its shortest period.
+    fn from_parts(
+        period: u128,
+        residues: Vec<u128>,
+        name: &'static str,
+        conversions: Conversions<T>,
+        overflow: OverflowGuard,
+    ) -> Self {
+        let (period, residues) = shortest_period(period, residues);
+
+        let mut set = Self {
+            period,
+            residues,
+            next: None,
+            name,
+            conversions,
+            overflow,
+        };
+        set.next = set.first_from(1);
+        set
+    }
+
+    /// Builds a new set with the same element type, name and overflow policy as this one.
+    fn with_residues(&self, period: u128, residues: Vec<u128>) -> Self {
+        Self::from_parts(
+            period,
+            residues,
+            self.name,
+            self.conversions,
+            OverflowGuard::new(self.overflow.policy()),
+        )
+    }
+
+    pub fn period(&self) -> u128 {
+        self.period
+    }
+
+    /// The residues modulo the period that are in the set, in ascending order.
+    pub fn residues(&self) -> &[u128] {
+        &self.residues
+    }
+
+    /// The fraction of the positive integers that are in the set.
+    pub fn density(&self) -> f64 {
+        self.residues.len() as f64 / self.period as f64
+    }
+
+    /// Returns the set of elements in this set or the other.
+    ///
+    /// Both sets are lifted to the lcm of their periods, so this takes time and memory in
+    /// proportion to the number of residues modulo that lcm. Returns None if the lcm doesn't fit
+    /// in a u128, or if the lifted sets would have more than 2^16 residues between them.
+    pub fn union_periodic(&self, other: &Self) -> Option<Self> {
+        let period = self.collapsible_lcm(other, self.lifted_len(other)?)?;
+        Some(self.with_residues(period, self.merge(other, period, |a, b| a || b)))
+    }
+
+    /// Returns the set of elements in both this set and the other.
+    ///
+    /// Each pair of residues is combined with the Chinese Remainder Theorem, so this takes time
+    /// in proportion to the product of the numbers of residues rather than to the lcm of the
+    /// periods. Returns None if that lcm doesn't fit in a u128, or if the product is more than
+    /// 2^16.
+    pub fn intersect_periodic(&self, other: &Self) -> Option<Self> {
+        let len = self.residues.len().checked_mul(other.residues.len())?;
+        let period = self.collapsible_lcm(other, len)?;
+
+        let mut residues = Vec::new();
+        for a in &self.residues {
+            for b in &other.residues {
+                if let Some(x) = modular::crt(a, &self.period, b, &other.period) {
+                    residues.push(x);
+                }
+            }
+        }
+        residues.sort_unstable();
+
+        Some(self.with_residues(period, residues))
+    }
+
+    /// Returns the set of elements in this set that are not in the other.
+    ///
+    /// Like `union_periodic`, this takes time and memory in proportion to the number of
+    /// residues modulo the lcm of the periods, and returns None under the same conditions.
+    pub fn difference_periodic(&self, other: &Self) -> Option<Self> {
+        let period = self.collapsible_lcm(other, self.lifted_len(other)?)?;
+        Some(self.with_residues(period, self.merge(other, period, |a, b| a && !b)))
+    }
+
+    /// Returns the set of positive integers that are not in this set. This takes time in
+    /// proportion to the period, and returns None if the complement would have more than 2^16
+    /// residues.
+    pub fn complement(&self) -> Option<Self> {
+        let len = usize::try_from(self.period - self.residues.len() as u128).ok()?;
+        if len > COLLAPSE_LIMIT {
+            return None;
+        }
+
+        let mut members = self.residues.iter().peekable();
+        let residues = (0..self.period)
+            .filter(|r| {
+                if members.peek() == Some(&r) {
+                    members.next();
+                    false
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        Some(self.with_residues(self.period, residues))
+    }
+
+    /// Returns the set of the elements that the union of this set and the other has left to
+    /// yield, if it can be collapsed. Like InfiniteUnion, each operand only contributes the
+    /// elements it hasn't yielded yet.
+    pub(crate) fn collapse_union(&self, other: &Self) -> Option<Self> {
+        if self.residues.is_empty() {
+            return Some(other.clone());
+        } else if other.residues.is_empty() {
+            return Some(self.clone());
+        }
+
+        self.same_policy(other)?;
+        let (a, b) = (self.next?, other.next?);
+        let start = a.min(b);
+
+        // the union can only resume from the lower of the two positions if the set that is
+        // further along hasn't already yielded anything past it
+        if self.first_from(start) != Some(a) || other.first_from(start) != Some(b) {
+            return None;
+        }
+
+        let mut union = self.union_periodic(other)?;
+        union.next = Some(start);
+
+        Some(union)
+    }
+
+    /// Returns the elements that both this set and the other have left to yield, if they can be
+    /// collapsed, in the way InfiniteIntersection yields them.
+    pub(crate) fn collapse_intersection(&self, other: &Self) -> Option<Self> {
+        self.same_policy(other)?;
+        self.has_next()?;
+        other.has_next()?;
+
+        // the intersection carries on from wherever the set that is further along is
+        let mut intersection = self.intersect_periodic(other)?;
+        intersection.next = self
+            .next
+            .max(other.next)
+            .and_then(|next| intersection.first_from(next));
+
+        Some(intersection)
+    }
+
+    /// Returns the elements that this set has left to yield that are not in the other set, if
+    /// they can be collapsed, in the way InfiniteDifference yields them. The other set is only
+    /// ever checked with `contains`, so unlike the other collapses, its overflow policy doesn't
+    /// matter.
+    pub(crate) fn collapse_difference(&self, other: &Self) -> Option<Self> {
+        self.has_next()?;
+        Some(self.difference_periodic(other)?.resumed_like(self))
+    }
+
+    /// Returns None if the sets have different overflow policies. The collapsed set can only have
+    /// one, and either policy would change how the other set's overflow is handled.
+    fn same_policy(&self, other: &Self) -> Option<()> {
+        if self.overflow.policy() == other.overflow.policy() {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Returns None if this set has ended early, for example by overflowing its element type,
+    /// since the sets collapsed from it would carry on past where it ended.
+    fn has_next(&self) -> Option<()> {
+        if self.next.is_none() && !self.residues.is_empty() {
+            None
+        } else {
+            Some(())
+        }
+    }
+
+    /// Moves this set on to where it would be if it had been iterated as far as `other`. Since
+    /// this set is a subset of `other`, that is its first element not before other's next one.
+    fn resumed_like(mut self, other: &Self) -> Self {
+        self.next = other.next.and_then(|next| self.first_from(next));
+        self
+    }
+
+    /// Moves this set so that `next` is the next element it yields, or returns None if `next`
+    /// isn't one of its elements. A `next` of None is only accepted by an empty set, since
+    /// otherwise the set ended early (for example by overflowing its element type).
+    pub(crate) fn resumed_at(mut self, next: Option<&T>) -> Option<Self> {
+        match next {
+            Some(next) => {
+                let next = (self.conversions.to_u128)(next)?;
+                if self.first_from(next) != Some(next) {
+                    return None;
+                }
+                self.next = Some(next);
+            }
+            None if self.residues.is_empty() => {}
+            None => return None,
+        }
+
+        Some(self)
+    }
+
+    /// Returns the smallest element that is at least `x`.
+    fn first_from(&self, x: u128) -> Option<u128> {
+        let x = x.max(1);
+        let (quotient, remainder) = x.div_rem(&self.period);
+        let base = quotient * self.period;
+
+        let i = self.residues.partition_point(|r| *r < remainder);
+        match self.residues.get(i) {
+            Some(r) => base.checked_add(*r),
+            None => base
+                .checked_add(self.period)?
+                .checked_add(*self.residues.first()?),
+        }
+    }
+
+    fn lcm_with(&self, other: &Self) -> Option<u128> {
+        modular::checked_lcm(&self.period, &other.period)
+    }
+
+    /// How many residues both sets have when lifted to the lcm of their periods.
+    fn lifted_len(&self, other: &Self) -> Option<usize> {
+        let period = self.lcm_with(other)?;
+        let lifted = |set: &Self| {
+            usize::try_from(period / set.period)
+                .ok()?
+                .checked_mul(set.residues.len())
+        };
+
+        lifted(self)?.checked_add(lifted(other)?)
+    }
+
+    /// Returns the lcm of the periods, if `len` residues are few enough to collapse into.
+    fn collapsible_lcm(&self, other: &Self, len: usize) -> Option<u128> {
+        if len > COLLAPSE_LIMIT {
+            return None;
+        }
+
+        self.lcm_with(other)
+    }
+
+    /// Lifts both sets to `period` (a multiple of both of their periods) and keeps the residues
+    /// for which `keep` is true, given whether each set has the residue.
+    fn merge(&self, other: &Self, period: u128, keep: impl Fn(bool, bool) -> bool) -> Vec<u128> {
+        let mut ours = self.lifted(period).peekable();
+        let mut theirs = other.lifted(period).peekable();
+
+        let mut residues = Vec::new();
+        loop {
+            let r = match (ours.peek(), theirs.peek()) {
+                (None, None) => break,
+                (Some(a), None) => *a,
+                (None, Some(b)) => *b,
+                (Some(a), Some(b)) => *a.min(b),
+            };
+            let in_ours = ours.next_if_eq(&r).is_some();
+            let in_theirs = theirs.next_if_eq(&r).is_some();
+
+            if keep(in_ours, in_theirs) {
+                residues.push(r);
+            }
+        }
+
+        residues
+    }
+
+    /// The residues of this set modulo `period`, a multiple of its own period, in ascending
+    /// order.
+    fn lifted(&self, period: u128) -> impl Iterator<Item = u128> + '_ {
+        (0..period / self.period)
+            .flat_map(move |k| self.residues.iter().map(move |r| k * self.period + r))
+    }
+}
+
+/// Reduces a period to the shortest one that describes the same residues. A set can only repeat
+/// every period / p if p divides both the period and the number of residues, so only those
+/// divisors are tried.
+fn shortest_period(mut period: u128, mut residues: Vec<u128>) -> (u128, Vec<u128>) {
+    if residues.is_empty() {
+        return (1, residues);
+    }
+
+    let mut p = 2;
+    while p as usize <= residues.len() {
+        let shorter = period / p;
+        let repeats = period.is_multiple_of(p)
+            && residues.len().is_multiple_of(p as usize)
+            && residues
+                .iter()
+                .all(|r| residues.binary_search(&((r + shorter) % period)).is_ok());
+
+        if repeats {
+            residues.retain(|r| *r < shorter);
+            period = shorter;
+        } else {
+            p += 1;
+        }
+    }
+
+    (period, residues)
+}
+
+impl<T> Clone for PeriodicSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            period: self.period,
+            residues: self.residues.clone(),
+            next: self.next,
+            name: self.name,
+            conversions: self.conversions,
+            overflow: self.overflow,
+        }
+    }
+}
+
+impl<T> InfiniteSet for PeriodicSet<T> {
+    fn contains(&self, x: &T) -> bool {
+        (self.conversions.residue_of)(x, self.period)
+            .is_some_and(|r| self.residues.binary_search(&r).is_ok())
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        // the residue class that holds every residue of the set, if there is one
+        let mut residues = self.residues.iter().map(|r| Residue::new(self.period, *r));
+        let residue = residues
+            .next()
+            .and_then(|first| residues.try_fold(first, |class, r| class.join(&r)));
+
+        SetFacts {
+            empty: self.residues.is_empty(),
+            residue: residue.filter(|class| class.modulus() > 1),
+            lower_bound: self.first_from(1).and_then(self.conversions.from_u128),
+            upper_bound: None,
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T>
+    where
+        T: Ord,
+    {
+        // nothing can be skipped before the positive integers start
+        let zero = (self.conversions.from_u128)(0).expect("0 fits in every element type");
+        if *lower_bound > zero {
+            match (self.conversions.to_u128)(lower_bound) {
+                Some(bound) if self.next.is_some_and(|next| next < bound) => {
+                    self.next = self.first_from(bound);
+                }
+                Some(_) => {}
+                // the bound is past u128::MAX, and so past every element the set can yield
+                None => self.next = None,
+            }
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.density()
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        Some(self.clone())
+    }
+}
+
+impl<T> Iterator for PeriodicSet<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match self.next.take() {
+            Some(current) => current,
+            // an empty set ends without overflowing
+            None if self.residues.is_empty() => return None,
+            None => return self.overflow.overflowed::<T>(self.name),
+        };
+
+        match (self.conversions.from_u128)(current) {
+            Some(element) => {
+                self.next = current.checked_add(1).and_then(|x| self.first_from(x));
+                Some(element)
+            }
+            None => self.overflow.overflowed::<T>(self.name),
+        }
+    }
+}
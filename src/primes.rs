@@ -0,0 +1,422 @@
+//! Primality testing and prime generation over the whole u128 range, used by InfinitePrimes.
+
+use crate::element::Element;
+
+/// Primes small enough to divide out by trial division before running any stronger test.
+const SMALL_PRIMES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Miller-Rabin with every base in SMALL_PRIMES is proven to be exact below this bound.
+const MILLER_RABIN_EXACT_BELOW: u128 = 3_317_044_064_679_887_385_961_981;
+
+/// Returns true if `n` is prime.
+///
+/// Below 3.3 * 10^24 this is a Miller-Rabin test with the first 13 primes as witnesses, which is
+/// proven to be exact in that range. Above it, a Baillie-PSW test (a base 2 Miller-Rabin test
+/// followed by a strong Lucas test) is used instead. Baillie-PSW has no known counterexamples,
+/// but isn't proven to have none. Either way there is no randomness, so the answer for a given
+/// `n` is always the same.
+pub(crate) fn is_prime(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+
+    for p in SMALL_PRIMES.iter() {
+        if n.is_multiple_of(*p) {
+            return n == *p;
+        }
+    }
+
+    if n < 41 * 41 {
+        return true;
+    }
+
+    if n < MILLER_RABIN_EXACT_BELOW {
+        SMALL_PRIMES
+            .iter()
+            .all(|base| is_strong_probable_prime(n, *base))
+    } else {
+        is_strong_probable_prime(n, 2) && is_strong_lucas_probable_prime(n)
+    }
+}
+
+/// Miller-Rabin with a fixed set of witnesses, for numbers too large for `is_prime`. This is
+/// only a probable prime test; a composite passes it with negligible but nonzero probability.
+pub(crate) fn is_probable_prime<T: Element>(n: &T) -> bool {
+    if *n < T::two() {
+        return false;
+    }
+
+    for p in SMALL_PRIMES.iter() {
+        let p = T::from_u128(*p).expect("small primes fit in every element type");
+        if n.is_multiple_of(&p) {
+            return *n == p;
+        }
+    }
+
+    let n_minus_one = n.clone() - T::one();
+    let mut d = n_minus_one.clone();
+    let mut s = 0;
+    while d.is_even() {
+        d = d / T::two();
+        s += 1;
+    }
+
+    SMALL_PRIMES.iter().all(|base| {
+        let base = T::from_u128(*base).expect("small primes fit in every element type");
+        let mut x = pow_mod_generic(base, d.clone(), n);
+        if x.is_one() || x == n_minus_one {
+            return true;
+        }
+
+        for _ in 1..s {
+            x = (x.clone() * x) % n.clone();
+            if x == n_minus_one {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+fn pow_mod_generic<T: Element>(mut base: T, mut exponent: T, modulus: &T) -> T {
+    let mut result = T::one();
+    base = base % modulus.clone();
+    while !exponent.is_zero() {
+        if exponent.is_odd() {
+            result = (result * base.clone()) % modulus.clone();
+        }
+        base = (base.clone() * base) % modulus.clone();
+        exponent = exponent / T::two();
+    }
+
+    result
+}
+
+/// Returns the largest integer whose square is at most `n`.
+pub(crate) fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    // start from the floating point estimate, then correct it
+    let mut root = (n as f64).sqrt() as u128;
+    while root.checked_mul(root).is_none_or(|square| square > n) {
+        root -= 1;
+    }
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= n)
+    {
+        root += 1;
+    }
+
+    root
+}
+
+fn add_mod(a: u128, b: u128, n: u128) -> u128 {
+    // a + b might not fit in a u128, so compare against what's left before wrapping around n
+    if a >= n - b {
+        a - (n - b)
+    } else {
+        a + b
+    }
+}
+
+fn sub_mod(a: u128, b: u128, n: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        n - (b - a)
+    }
+}
+
+fn mul_mod(a: u128, b: u128, n: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % n;
+    }
+
+    // the product doesn't fit, so build it up one bit of b at a time
+    let mut result = 0;
+    let mut a = a % n;
+    let mut b = b;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, n);
+        }
+        a = add_mod(a, a, n);
+        b >>= 1;
+    }
+
+    result
+}
+
+fn pow_mod(mut base: u128, mut exponent: u128, n: u128) -> u128 {
+    let mut result = 1;
+    base %= n;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, n);
+        }
+        base = mul_mod(base, base, n);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// Halves `x` modulo the odd number `n`.
+fn half_mod(x: u128, n: u128) -> u128 {
+    if x.is_multiple_of(2) {
+        x / 2
+    } else {
+        // (x + n) / 2, without letting x + n overflow
+        x / 2 + n / 2 + 1
+    }
+}
+
+/// The Miller-Rabin test of odd `n` to the given base.
+fn is_strong_probable_prime(n: u128, base: u128) -> bool {
+    let mut d = n - 1;
+    let s = d.trailing_zeros();
+    d >>= s;
+
+    let mut x = pow_mod(base, d, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = mul_mod(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// The Jacobi symbol (a/n) for odd `n`.
+fn jacobi(mut a: u128, mut n: u128) -> i8 {
+    let mut result = 1;
+    a %= n;
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+
+    if n == 1 {
+        result
+    } else {
+        0
+    }
+}
+
+/// Reduces the signed number `x` modulo `n`.
+fn signed_mod(x: i128, n: u128) -> u128 {
+    let magnitude = x.unsigned_abs() % n;
+    if x < 0 && magnitude != 0 {
+        n - magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// The strong Lucas probable prime test of odd `n`, with Selfridge's choice of parameters.
+fn is_strong_lucas_probable_prime(n: u128) -> bool {
+    // no D below can have (D/n) = -1 when n is a perfect square
+    let root = isqrt(n);
+    if root * root == n {
+        return false;
+    }
+
+    // find the first D in 5, -7, 9, -11, ... with (D/n) = -1
+    let mut d: i128 = 5;
+    loop {
+        match jacobi(signed_mod(d, n), n) {
+            -1 => break,
+            0 if d.unsigned_abs() != n => return false,
+            _ => d = if d > 0 { -(d + 2) } else { -d + 2 },
+        }
+    }
+
+    // with P = 1 and Q = (1 - D) / 4, n is a strong Lucas probable prime if U_k = 0 or
+    // V_(k * 2^r) = 0 for some r < s, where n + 1 = k * 2^s with k odd
+    let big_d = signed_mod(d, n);
+    let q = signed_mod((1 - d) / 4, n);
+    let n_plus_one = n + 1;
+    let s = n_plus_one.trailing_zeros();
+    let k = n_plus_one >> s;
+
+    let mut u = 1;
+    let mut v = 1;
+    let mut q_k = q;
+    for bit in (0..(127 - k.leading_zeros())).rev() {
+        // double the index
+        u = mul_mod(u, v, n);
+        v = sub_mod(mul_mod(v, v, n), add_mod(q_k, q_k, n), n);
+        q_k = mul_mod(q_k, q_k, n);
+
+        if k >> bit & 1 == 1 {
+            // and add one to it
+            let next_u = half_mod(add_mod(u, v, n), n);
+            v = half_mod(add_mod(mul_mod(big_d, u, n), v, n), n);
+            u = next_u;
+            q_k = mul_mod(q_k, q, n);
+        }
+    }
+
+    if u == 0 || v == 0 {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = sub_mod(mul_mod(v, v, n), add_mod(q_k, q_k, n), n);
+        if v == 0 {
+            return true;
+        }
+        q_k = mul_mod(q_k, q_k, n);
+    }
+
+    false
+}
+
+/// How many numbers are sieved at once.
+const SEGMENT_LEN: u128 = 1 << 16;
+
+/// The largest prime used for sieving. Past `SIEVE_LIMIT^2`, sieving only removes numbers with
+/// small factors, and whatever survives is checked with `is_prime`.
+const SIEVE_LIMIT: u64 = 1 << 22;
+
+/// A segmented sieve of Eratosthenes that generates the primes in order, one segment at a time.
+///
+/// Only the primes up to the square root of the current segment (at most SIEVE_LIMIT) are kept in
+/// memory, so unlike a plain sieve it can carry on through the whole u128 range.
+pub(crate) struct SegmentedPrimes {
+    /// Every prime up to `sieving_limit`, used to cross off composites in each segment.
+    sieving_primes: Vec<u64>,
+    sieving_limit: u64,
+
+    /// Where the next segment starts.
+    low: u128,
+
+    /// The primes found in the current segment that haven't been returned yet, in descending
+    /// order so that the next one can be popped off the end.
+    found: Vec<u128>,
+}
+
+impl SegmentedPrimes {
+    pub(crate) fn new() -> Self {
+        Self {
+            sieving_primes: Vec::new(),
+            sieving_limit: 1,
+            low: 0,
+            found: Vec::new(),
+        }
+    }
+
+    /// Skips every prime below `bound`, so that the next one returned is the first prime at
+    /// least `bound`. Never moves backwards.
+    pub(crate) fn skip_to(&mut self, bound: u128) {
+        while self.found.last().is_some_and(|p| *p < bound) {
+            self.found.pop();
+        }
+
+        // if the current segment has run out, the next one can start at the bound
+        if self.found.is_empty() && bound > self.low {
+            self.low = bound;
+        }
+    }
+
+    /// Where the sieve is up to: the next prime it returns is at least this.
+    pub(crate) fn position(&self) -> u128 {
+        self.found.last().copied().unwrap_or(self.low)
+    }
+
+    /// Makes sure that `sieving_primes` holds every prime up to at least `limit`.
+    fn extend_sieving_primes(&mut self, limit: u64) {
+        if limit <= self.sieving_limit {
+            return;
+        }
+
+        // grow geometrically so that the sieving primes are only rebuilt a few times
+        let limit = limit.max(self.sieving_limit * 2).min(SIEVE_LIMIT) as usize;
+        let mut composite = vec![false; limit + 1];
+        self.sieving_primes.clear();
+        for i in 2..=limit {
+            if !composite[i] {
+                self.sieving_primes.push(i as u64);
+                for multiple in (i * i..=limit).step_by(i) {
+                    composite[multiple] = true;
+                }
+            }
+        }
+
+        self.sieving_limit = limit as u64;
+    }
+
+    /// Sieves the next segment into `found`. Returns false if there are no segments left.
+    fn sieve_next_segment(&mut self) -> bool {
+        let low = self.low;
+        let high = match low.checked_add(SEGMENT_LEN) {
+            Some(high) => high,
+            // u128::MAX itself is composite, so nothing is lost by stopping just short of it
+            None if low < u128::MAX => u128::MAX,
+            None => return false,
+        };
+
+        let root = isqrt(high - 1);
+        self.extend_sieving_primes(root.min(u128::from(SIEVE_LIMIT)) as u64);
+        let fully_sieved = root <= u128::from(self.sieving_limit);
+
+        let mut composite = vec![false; (high - low) as usize];
+        for p in self.sieving_primes.iter().map(|p| u128::from(*p)) {
+            if p * p >= high {
+                break;
+            }
+
+            // start from p^2, since smaller multiples of p have a smaller prime factor too. near
+            // u128::MAX the next multiple may not fit, in which case it is past the segment
+            let first = match low.div_ceil(p).checked_mul(p) {
+                Some(first) => first.max(p * p),
+                None => continue,
+            };
+            let mut multiple = Some(first);
+            while let Some(m) = multiple.filter(|m| *m < high) {
+                composite[(m - low) as usize] = true;
+                multiple = m.checked_add(p);
+            }
+        }
+
+        self.found = (low.max(2)..high)
+            .filter(|n| !composite[(n - low) as usize] && (fully_sieved || is_prime(*n)))
+            .collect();
+        self.found.reverse();
+        self.low = high;
+
+        true
+    }
+}
+
+impl Iterator for SegmentedPrimes {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        while self.found.is_empty() {
+            if !self.sieve_next_segment() {
+                return None;
+            }
+        }
+
+        self.found.pop()
+    }
+}
@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use crate::error::OverflowError;
+use crate::facts::SetFacts;
+use crate::infinite_set::InfiniteSet;
+
+/// A pair of elements, the element type of an InfiniteProduct.
+///
+/// Pairs are ordered the way Szudzik's pairing function enumerates them, rather than
+/// lexicographically like tuples: first by the larger of the two values, so that the pairs are
+/// taken in square shells that each hold finitely many pairs, and then within a shell, the pairs
+/// (a, m) with a < m by a, followed by the pairs (m, b) with b <= m by b. For the natural numbers
+/// that goes (0, 0), (0, 1), (1, 0), (1, 1), (0, 2), (1, 2), (2, 0), ...
+///
+/// Since this is an order on the values and not on positions in some set, the products of
+/// different sets can be combined with InfiniteUnion like any other sets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pair<T>(pub T, pub T);
+
+impl<T: Ord> Pair<T> {
+    /// The larger of the two values, which is the shell that the pair is in.
+    fn shell(&self) -> &T {
+        std::cmp::max(&self.0, &self.1)
+    }
+
+    /// Whether the pair is on the second side of its shell, where the larger value comes first.
+    fn is_second_side(&self) -> bool {
+        self.0 >= self.1
+    }
+
+    /// Where the pair is along its side of the shell: its smaller value.
+    fn position(&self) -> &T {
+        if self.is_second_side() {
+            &self.1
+        } else {
+            &self.0
+        }
+    }
+}
+
+impl<T: Ord> Ord for Pair<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.shell()
+            .cmp(other.shell())
+            .then_with(|| self.is_second_side().cmp(&other.is_second_side()))
+            .then_with(|| self.position().cmp(other.position()))
+    }
+}
+
+impl<T: Ord> PartialOrd for Pair<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> From<Pair<T>> for (T, T) {
+    fn from(pair: Pair<T>) -> Self {
+        (pair.0, pair.1)
+    }
+}
+
+/// The cartesian product of two infinite sets: every pair (a, b) with a in the first set and b
+/// in the second. InfiniteProduct is also an InfiniteSet, whose elements are Pairs.
+///
+/// The pairs are enumerated in the order of `Pair`, one shell at a time: once the sets have been
+/// merged up to a value m, the shell of m holds every pair of values seen so far whose larger
+/// value is m. Every shell is finite, so every pair is reached after finitely many others, even
+/// though both sets go on forever. `contains` checks each value against its own set.
+///
+/// To build the shells, the product keeps every value it has pulled from either set, so its
+/// memory grows with the square root of the number of pairs it has yielded.
+pub struct InfiniteProduct<A, B>
+where
+    A: InfiniteSet,
+{
+    first: A,
+    second: B,
+
+    /// Every value pulled from each set so far, in ascending order.
+    first_seen: Vec<A::Item>,
+    second_seen: Vec<A::Item>,
+
+    /// The stored next values of both sets. A stored value of None means that its set has
+    /// ended.
+    first_next: Option<A::Item>,
+    second_next: Option<A::Item>,
+
+    /// The pairs of the current shell that haven't been yielded yet.
+    shell: VecDeque<Pair<A::Item>>,
+
+    /// Whether the next values have been pulled from the sets yet.
+    started: bool,
+}
+
+impl<A, B> InfiniteProduct<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord + Clone,
+{
+    pub fn new(first: A, second: B) -> Self {
+        Self {
+            first,
+            second,
+            first_seen: Vec::new(),
+            second_seen: Vec::new(),
+            first_next: None,
+            second_next: None,
+            shell: VecDeque::new(),
+            started: false,
+        }
+    }
+
+    /// Pulls the next value m of the merged sets, and fills `shell` with the pairs whose larger
+    /// value is m. The shell is left empty if none of the values seen so far pair up with m yet.
+    /// Returns false once there are no shells left.
+    fn fill_shell(&mut self) -> bool {
+        if !self.started {
+            self.first_next = self.first.next();
+            self.second_next = self.second.next();
+            self.started = true;
+        }
+
+        // a set that has ended with no values leaves nothing to pair up with
+        let first_empty = self.first_next.is_none() && self.first_seen.is_empty();
+        let second_empty = self.second_next.is_none() && self.second_seen.is_empty();
+        if first_empty || second_empty {
+            return false;
+        }
+
+        let m = match (&self.first_next, &self.second_next) {
+            (None, None) => return false,
+            (Some(a), None) => a.clone(),
+            (None, Some(b)) => b.clone(),
+            (Some(a), Some(b)) => a.min(b).clone(),
+        };
+
+        // the pairs (a, m) with a < m come first, then the pairs (m, b) with b <= m. every value
+        // seen so far is at most m, since the sets are merged in ascending order
+        if self.second_next.as_ref() == Some(&m) {
+            self.second_seen.push(m.clone());
+            self.second_next = self.second.next();
+
+            let below = self.first_seen.iter().filter(|a| **a < m);
+            let pairs = below.map(|a| Pair(a.clone(), m.clone()));
+            self.shell.extend(pairs);
+        }
+        if self.first_next.as_ref() == Some(&m) {
+            self.first_seen.push(m.clone());
+            self.first_next = self.first.next();
+
+            let pairs = self.second_seen.iter().map(|b| Pair(m.clone(), b.clone()));
+            self.shell.extend(pairs);
+        }
+
+        true
+    }
+}
+
+impl<A, B> InfiniteSet for InfiniteProduct<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord + Clone,
+{
+    fn contains(&self, x: &Pair<A::Item>) -> bool {
+        self.first.contains(&x.0) && self.second.contains(&x.1)
+    }
+
+    fn facts(&self) -> SetFacts<Pair<A::Item>> {
+        // the least and greatest pairs are made of the least and greatest values of each set
+        let first = self.first.facts();
+        let second = self.second.facts();
+        SetFacts {
+            empty: first.empty || second.empty,
+            lower_bound: first
+                .lower_bound
+                .zip(second.lower_bound)
+                .map(|(a, b)| Pair(a, b)),
+            upper_bound: first
+                .upper_bound
+                .zip(second.upper_bound)
+                .map(|(a, b)| Pair(a, b)),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.first.overflow().or_else(|| self.second.overflow())
+    }
+}
+
+impl<A, B> Iterator for InfiniteProduct<A, B>
+where
+    A: InfiniteSet,
+    B: InfiniteSet<Item = A::Item>,
+    A::Item: Ord + Clone,
+{
+    type Item = Pair<A::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.shell.is_empty() {
+            if !self.fill_shell() {
+                return None;
+            }
+        }
+
+        self.shell.pop_front()
+    }
+}
@@ -0,0 +1,45 @@
+//! Integer powers and roots over any Element, for the sets whose membership tests need them.
+
+use crate::element::Element;
+
+/// Returns x^k, or None if it doesn't fit in T.
+pub(crate) fn checked_pow<T: Element>(x: &T, k: u32) -> Option<T> {
+    (0..k).try_fold(T::one(), |power, _| power.checked_mul(x))
+}
+
+/// Returns the largest integer whose k-th power is at most the non-negative `n`.
+pub(crate) fn root<T: Element>(n: &T, k: u32) -> T {
+    if k == 1 || *n < T::two() {
+        return n.clone();
+    }
+
+    // start from the floating point estimate if there is one, or else double up from 1 until
+    // the k-th power passes n
+    let mut x = n
+        .to_f64()
+        .and_then(|n| T::from_f64(n.powf(1.0 / f64::from(k))))
+        .filter(|x| *x > T::zero())
+        .unwrap_or_else(|| {
+            let mut x = T::one();
+            while checked_pow(&x, k).is_some_and(|power| power <= *n) {
+                x = x * T::two();
+            }
+            x
+        });
+
+    // a step of Newton's method from anywhere lands on or above the root, and from there every
+    // step goes down until it reaches the floor of the root
+    let k_t = T::from_u32(k).expect("small exponents fit in every element type");
+    let step = |x: &T| {
+        let quotient = checked_pow(x, k - 1).map_or_else(T::zero, |power| n.clone() / power);
+        ((k_t.clone() - T::one()) * x.clone() + quotient) / k_t.clone()
+    };
+    x = step(&x);
+    loop {
+        let next = step(&x);
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
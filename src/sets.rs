@@ -1,154 +1,1329 @@
-use super::infinite_set::InfiniteSet;
+use std::any::type_name;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use num_traits::Signed;
+
+use crate::bidirectional::BidirectionalSet;
+use crate::element::Element;
+use crate::error::OverflowError;
+use crate::facts::{Residue, SetFacts};
+use crate::infinite_set::InfiniteSet;
+use crate::modular;
+use crate::overflow::{OverflowGuard, OverflowPolicy};
+use crate::primes::{self, SegmentedPrimes};
+use crate::roots;
+
+pub use crate::figurate::{
+    InfiniteCubes, InfiniteKthPowers, InfinitePolygonalNumbers, InfinitePyramidalNumbers,
+    InfiniteSquares, InfiniteTriangularNumbers,
+};
+pub use crate::finite::{FiniteRange, FiniteSet};
+pub use crate::periodic::PeriodicSet;
+
+/// The empty set. It contains nothing and its iterator ends immediately, which makes it useful as
+/// the result of set algebra that is known to have no elements.
+pub struct EmptySet<T> {
+    element: PhantomData<T>,
+}
+
+impl<T> EmptySet<T> {
+    pub fn new() -> Self {
+        Self {
+            element: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for EmptySet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> InfiniteSet for EmptySet<T> {
+    fn contains(&self, _x: &T) -> bool {
+        false
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            empty: true,
+            ..SetFacts::default()
+        }
+    }
+
+    fn estimated_density(&self) -> f64 {
+        0.0
+    }
+}
+
+impl<T> Iterator for EmptySet<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        None
+    }
+}
 
 /// Infinite set of positive ints (excludes zero)
-#[derive(Default)]
-pub struct InfinitePositiveInts {
-    /// Defaults to zero via derivation of Default.
-    current: u128
+pub struct InfinitePositiveInts<T = u128> {
+    /// The next int to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    overflow: OverflowGuard,
 }
 
-impl InfinitePositiveInts {
+impl<T: Element> InfinitePositiveInts<T> {
     pub fn new() -> Self {
-        Default::default()
+        Self {
+            next: Some(T::one()),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
     }
 }
 
-impl InfiniteSet for InfinitePositiveInts {
-    fn contains(&self, x: &u128) -> bool {
-        *x > 0
+impl<T: Element> Default for InfinitePositiveInts<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Iterator for InfinitePositiveInts {
-    type Item = u128;
+impl<T: Element> InfiniteSet for InfinitePositiveInts<T> {
+    fn contains(&self, x: &T) -> bool {
+        *x > T::zero()
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            lower_bound: Some(T::one()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        // every int past the current one is in the set, so the bound itself is next
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.next = Some(lower_bound.clone());
+        }
+
+        self.next()
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        PeriodicSet::describing(
+            "InfinitePositiveInts",
+            1,
+            vec![0],
+            self.overflow.policy(),
+            self.next.as_ref(),
+        )
+    }
+}
+
+impl<T: Element> Iterator for InfinitePositiveInts<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // increments the current number and returns it
-        self.current += 1;
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfinitePositiveInts"),
+        };
+
+        // increments the current number for next time, then returns it
+        self.next = current.checked_add(&T::one());
 
-        Some(self.current)
+        Some(current)
     }
 }
 
 /// Infinite set of prime numbers
-pub struct InfinitePrimes {
-    primes: primal::Primes,
+///
+/// The primes are generated with a segmented sieve, so they keep coming across the whole u128
+/// range. The sieve stops at u128::MAX, so a set of an element type that goes further (such as
+/// `BigUint`) ends there without an OverflowError.
+///
+/// `contains` is proven exact below 3.3 * 10^24. From there up to u128::MAX it uses the
+/// Baillie-PSW test, which has no known counterexamples but isn't proven exact, and elements
+/// beyond u128::MAX are checked with a Miller-Rabin probable prime test.
+pub struct InfinitePrimes<T = u128> {
+    primes: SegmentedPrimes,
+    element: PhantomData<T>,
+
+    overflow: OverflowGuard,
 }
 
-impl InfinitePrimes {
+impl<T: Element> InfinitePrimes<T> {
     pub fn new() -> Self {
         Self {
-            primes: primal::Primes::all(),
+            primes: SegmentedPrimes::new(),
+            element: PhantomData,
+            overflow: OverflowGuard::default(),
         }
     }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
 }
 
-impl InfiniteSet for InfinitePrimes {
-    fn contains(&self, x: &u128) -> bool {
-        primal::is_prime(*x as u64)
+impl<T: Element> Default for InfinitePrimes<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Iterator for InfinitePrimes {
-    type Item = u128;
+impl<T: Element> InfiniteSet for InfinitePrimes<T> {
+    fn contains(&self, x: &T) -> bool {
+        if *x < T::zero() {
+            return false;
+        }
+
+        match x.to_u128() {
+            Some(x) => primes::is_prime(x),
+            None => primes::is_probable_prime(x),
+        }
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            lower_bound: Some(T::two()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        // sieve onwards from the bound instead of generating every prime before it. a bound
+        // past u128::MAX is past every prime the sieve can generate
+        if *lower_bound > T::zero() {
+            self.primes
+                .skip_to(lower_bound.to_u128().unwrap_or(u128::MAX));
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // the prime number theorem
+        let near = self.primes.position().max(3) as f64;
+        1.0 / near.ln()
+    }
+}
+
+impl<T: Element> Iterator for InfinitePrimes<T> {
+    type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
-        self.primes.next().map(|u| u as u128)
+        // the next prime overflows if it doesn't fit in T. once the sieve runs out at
+        // u128::MAX, a T that goes further hasn't overflowed; the set just can't go on
+        match self.primes.next().map(T::from_u128) {
+            Some(Some(prime)) => Some(prime),
+            None if T::exceeds_u128() => None,
+            _ => self.overflow.overflowed::<T>("InfinitePrimes"),
+        }
     }
 }
 
 /// Infinite set of positive even numbers (excludes zero)
-#[derive(Default)]
-pub struct InfiniteEvens {
-    /// Defaults to 0 via derivation of Default
-    current: u128,
+pub struct InfiniteEvens<T = u128> {
+    /// The next even number to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    overflow: OverflowGuard,
 }
 
-impl InfiniteEvens {
+impl<T: Element> InfiniteEvens<T> {
     pub fn new() -> Self {
-        Default::default()
+        Self {
+            next: Some(T::two()),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
     }
 }
 
-impl InfiniteSet for InfiniteEvens {
-    fn contains(&self, x: &u128) -> bool {
-        *x > 0 && x % 2 == 0
+impl<T: Element> Default for InfiniteEvens<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl Iterator for InfiniteEvens {
-    type Item = u128;
+impl<T: Element> InfiniteSet for InfiniteEvens<T> {
+    fn contains(&self, x: &T) -> bool {
+        *x > T::zero() && x.is_even()
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            residue: Some(Residue::new(2, 0)),
+            lower_bound: Some(T::two()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.next = modular::first_at_least(&T::two(), &T::two(), lower_bound);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        0.5
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        PeriodicSet::describing(
+            "InfiniteEvens",
+            2,
+            vec![0],
+            self.overflow.policy(),
+            self.next.as_ref(),
+        )
+    }
+}
+
+impl<T: Element> Iterator for InfiniteEvens<T> {
+    type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
-        self.current += 2;
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteEvens"),
+        };
+
+        self.next = current.checked_add(&T::two());
 
-        Some(self.current)
+        Some(current)
     }
 }
 
 /// Infinite set of positive odd numbers
-pub struct InfiniteOdds {
-    current: u128,
+pub struct InfiniteOdds<T = u128> {
+    /// The next odd number to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    overflow: OverflowGuard,
 }
 
-impl InfiniteOdds {
+impl<T: Element> InfiniteOdds<T> {
     pub fn new() -> Self {
         Self {
-            current: 1,
+            next: Some(T::one()),
+            overflow: OverflowGuard::default(),
         }
     }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element> Default for InfiniteOdds<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl InfiniteSet for InfiniteOdds {
-    fn contains(&self, x: &u128) -> bool {
-        *x > 0 && x % 2 == 1
+impl<T: Element> InfiniteSet for InfiniteOdds<T> {
+    fn contains(&self, x: &T) -> bool {
+        *x > T::zero() && x.is_odd()
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            residue: Some(Residue::new(2, 1)),
+            lower_bound: Some(T::one()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.next = modular::first_at_least(&T::one(), &T::two(), lower_bound);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        0.5
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        PeriodicSet::describing(
+            "InfiniteOdds",
+            2,
+            vec![1],
+            self.overflow.policy(),
+            self.next.as_ref(),
+        )
     }
 }
 
-impl Iterator for InfiniteOdds {
-    type Item = u128;
+impl<T: Element> Iterator for InfiniteOdds<T> {
+    type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
         // save current number before raising it
-        let result = Some(self.current);
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteOdds"),
+        };
 
         // advance to next odd number
-        self.current += 2;
+        self.next = current.checked_add(&T::two());
 
         // return the odd number we saved
-        result
+        Some(current)
     }
 }
 
-/// Infinite set for powers of two
-pub struct InfiniteTwoPowers {
-    current: u128,
+/// Infinite set of the powers of a base (1, base, base^2, ...)
+pub struct InfinitePowers<T = u128> {
+    base: T,
+
+    /// The next power to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    /// The name of the set, for its OverflowError.
+    name: &'static str,
+
+    overflow: OverflowGuard,
 }
 
-impl InfiniteTwoPowers {
-    pub fn new() -> Self {
+impl<T: Element> InfinitePowers<T> {
+    /// Creates the set of powers of `base`.
+    ///
+    /// Panics if `base` is less than 2, since the powers of 0 and 1 aren't infinite.
+    pub fn new(base: T) -> Self {
+        Self::named(base, "InfinitePowers")
+    }
+
+    /// Creates the set of powers of `base`, which reports its overflow under `name`.
+    fn named(base: T, name: &'static str) -> Self {
+        assert!(
+            base >= T::two(),
+            "the base of InfinitePowers must be at least 2"
+        );
+
         Self {
-            current: 1,
+            base,
+            next: Some(T::one()),
+            name,
+            overflow: OverflowGuard::default(),
         }
     }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+
+    pub fn base(&self) -> &T {
+        &self.base
+    }
 }
 
-impl InfiniteSet for InfiniteTwoPowers {
-    fn contains(&self, x: &u128) -> bool {
-        let log = (*x as f64).log2();
+impl<T: Element> InfiniteSet for InfinitePowers<T> {
+    fn contains(&self, x: &T) -> bool {
+        if *x <= T::zero() {
+            return false;
+        }
 
-        // checks if the log2 is an int. if it is, that means that x is a power of 2
-        *x > 0 && log.fract() != 0.0
+        // divide out the base for as long as the division is exact. x is a power of the base
+        // exactly when that leaves nothing but 1
+        let mut rest = x.clone();
+        while rest.is_multiple_of(&self.base) {
+            rest = rest / self.base.clone();
+        }
+
+        rest.is_one()
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        // base^k = 1 (mod base - 1) for every k, which says nothing for base 2
+        let residue = self
+            .base
+            .to_u128()
+            .filter(|base| *base > 2)
+            .map(|base| Residue::new(base - 1, 1));
+
+        SetFacts {
+            residue,
+            lower_bound: Some(T::one()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // the next power after x is x * base, so there is one power in the (base - 1) * x
+        // integers past x
+        let near = self.next.as_ref().and_then(T::to_f64);
+        let base = self.base.to_f64();
+        match (near, base) {
+            (Some(near), Some(base)) => 1.0 / ((base - 1.0) * near),
+            _ => 0.0,
+        }
     }
 }
 
-impl Iterator for InfiniteTwoPowers {
-    type Item = u128;
+impl<T: Element> Iterator for InfinitePowers<T> {
+    type Item = T;
     fn next(&mut self) -> Option<Self::Item> {
         // save current number before raising it
-        let result = Some(self.current);
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>(self.name),
+        };
 
-        // advance to next power of 2 by multiplying by 2
-        self.current *= 2;
+        // advance to next power by multiplying by the base
+        self.next = current.checked_mul(&self.base);
 
         // return the number we saved
-        result
+        Some(current)
+    }
+}
+
+/// Infinite set for powers of two. This is `InfinitePowers` with a base of 2.
+pub struct InfiniteTwoPowers<T = u128> {
+    powers: InfinitePowers<T>,
+}
+
+impl<T: Element> InfiniteTwoPowers<T> {
+    pub fn new() -> Self {
+        Self {
+            powers: InfinitePowers::named(T::two(), "InfiniteTwoPowers"),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            powers: self.powers.with_overflow_policy(policy),
+        }
+    }
+}
+
+impl<T: Element> Default for InfiniteTwoPowers<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteTwoPowers<T> {
+    fn contains(&self, x: &T) -> bool {
+        self.powers.contains(x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.powers.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.powers.overflow()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        self.powers.seek(lower_bound)
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.powers.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfiniteTwoPowers<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.powers.next()
+    }
+}
+
+/// Infinite set of the arithmetic progression start, start + step, start + 2 * step, ...
+///
+/// Intersecting two progressions with `InfiniteSet::intersect` doesn't search them: the
+/// intersection is found in closed form with `intersect_progression`.
+#[derive(Clone)]
+pub struct ArithmeticProgression<T = u128> {
+    start: T,
+    step: T,
+
+    /// The next term to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element> ArithmeticProgression<T> {
+    /// Creates the progression that starts at `start` and goes up by `step`.
+    ///
+    /// Panics if `step` isn't positive, since the progression would then repeat or descend.
+    pub fn new(start: T, step: T) -> Self {
+        assert!(
+            step > T::zero(),
+            "the step of ArithmeticProgression must be positive"
+        );
+
+        Self {
+            next: Some(start.clone()),
+            start,
+            step,
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+
+    pub fn start(&self) -> &T {
+        &self.start
+    }
+
+    pub fn step(&self) -> &T {
+        &self.step
+    }
+
+    /// Returns the progression of the elements in both this progression and the other, or None
+    /// if they have none in common. This works on the whole progressions, however far either has
+    /// been iterated, and keeps this progression's overflow policy.
+    ///
+    /// The result is found in closed form with the Chinese Remainder Theorem: its step is the lcm
+    /// of the two steps, and it starts at the first common element.
+    ///
+    /// Returns an OverflowError if the common elements can't be represented as a progression of
+    /// `T`, because either the lcm of the two steps or the first common element doesn't fit in
+    /// `T`.
+    pub fn intersect_progression(&self, other: &Self) -> Result<Option<Self>, OverflowError> {
+        let overflow = OverflowError {
+            set: "ArithmeticProgression",
+            element: type_name::<T>(),
+        };
+        let step = modular::checked_lcm(&self.step, &other.step).ok_or(overflow)?;
+
+        let remainder = match modular::crt(
+            &self.start.mod_floor(&self.step),
+            &self.step,
+            &other.start.mod_floor(&other.step),
+            &other.step,
+        ) {
+            Some(remainder) => remainder,
+            None => return Ok(None),
+        };
+
+        // the first common element is the first element of the combined residue class that is
+        // at least both starts
+        let floor = self.start.clone().max(other.start.clone());
+        let start = modular::first_at_least(&remainder, &step, &floor).ok_or(overflow)?;
+
+        Ok(Some(
+            Self::new(start, step).with_overflow_policy(self.overflow.policy()),
+        ))
+    }
+}
+
+impl<T: Element> InfiniteSet for ArithmeticProgression<T> {
+    fn contains(&self, x: &T) -> bool {
+        // comparing residues rather than dividing x - start, which can overflow for signed types
+        *x >= self.start && x.mod_floor(&self.step) == self.start.mod_floor(&self.step)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        // a step of 1 is every integer past the start, which says nothing about residues
+        let residue = match (
+            self.step.to_u128(),
+            self.start.mod_floor(&self.step).to_u128(),
+        ) {
+            (Some(step), Some(remainder)) if step > 1 => Some(Residue::new(step, remainder)),
+            _ => None,
+        };
+
+        SetFacts {
+            residue,
+            lower_bound: Some(self.start.clone()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn seek(&mut self, lower_bound: &T) -> Option<T> {
+        if self.next.as_ref().is_some_and(|next| next < lower_bound) {
+            self.next = modular::first_at_least(&self.start, &self.step, lower_bound);
+        }
+
+        self.next()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.step.to_f64().map_or(0.0, |step| 1.0 / step)
+    }
+
+    fn as_periodic(&self) -> Option<PeriodicSet<T>> {
+        // the progression is periodic when it starts at its first positive term, that is
+        // when 0 < start <= step
+        if self.start <= T::zero() || self.start > self.step {
+            return None;
+        }
+
+        PeriodicSet::describing(
+            "ArithmeticProgression",
+            self.step.to_u128()?,
+            vec![self.start.to_u128()?],
+            self.overflow.policy(),
+            self.next.as_ref(),
+        )
+    }
+
+    fn as_progression(&self) -> Option<ArithmeticProgression<T>> {
+        Some(self.clone())
+    }
+
+    fn intersect_closed_form(
+        &self,
+        other: &dyn InfiniteSet<Item = T>,
+    ) -> Option<Box<dyn InfiniteSet<Item = T>>>
+    where
+        T: 'static,
+    {
+        let other = other.as_progression()?;
+
+        // the intersection carries on from wherever the progression that is further along is.
+        // one that has already ended is left to the search, which ends with it
+        let resume = self.next.clone()?.max(other.next.clone()?);
+
+        match self.intersect_progression(&other) {
+            Ok(Some(mut both)) => {
+                both.next = modular::first_at_least(&both.start, &both.step, &resume);
+                Some(Box::new(both))
+            }
+            Ok(None) => Some(Box::new(FiniteSet::new(Vec::new()))),
+            // common elements past T are also left to the search, which ends by overflowing
+            Err(_) => None,
+        }
+    }
+}
+
+impl<T: Element> Iterator for ArithmeticProgression<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("ArithmeticProgression"),
+        };
+
+        self.next = current.checked_add(&self.step);
+
+        Some(current)
+    }
+}
+
+/// Infinite set of factorials (1, 2, 6, 24, ...). Factorials outgrow every primitive type
+/// quickly (34! doesn't fit in a u128), so this set is best made of `BigUint` with the `bigint`
+/// feature enabled.
+pub struct InfiniteFactorials<T = u128> {
+    /// The next factorial to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    /// The number whose factorial is `next`.
+    n: T,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element> InfiniteFactorials<T> {
+    pub fn new() -> Self {
+        Self {
+            next: Some(T::one()),
+            n: T::one(),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element> Default for InfiniteFactorials<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteFactorials<T> {
+    fn contains(&self, x: &T) -> bool {
+        if *x <= T::zero() {
+            return false;
+        }
+
+        // divide out 2, 3, 4, ... for as long as the division is exact. x is a factorial exactly
+        // when that leaves nothing but 1
+        let mut rest = x.clone();
+        let mut divisor = T::two();
+        while rest.is_multiple_of(&divisor) {
+            rest = rest / divisor.clone();
+            divisor = divisor + T::one();
+        }
+
+        rest.is_one()
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            lower_bound: Some(T::one()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // the next factorial after x is about n * x
+        let near = self.next.as_ref().and_then(T::to_f64);
+        let n = self.n.to_f64();
+        match (near, n) {
+            (Some(near), Some(n)) => 1.0 / (n * near),
+            _ => 0.0,
+        }
+    }
+}
+
+impl<T: Element> Iterator for InfiniteFactorials<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteFactorials"),
+        };
+
+        // advance to the next factorial, keeping the current one to return. n can't overflow
+        // before the factorial does
+        self.n = self.n.clone() + T::one();
+        self.next = current.checked_mul(&self.n);
+
+        Some(current)
+    }
+}
+
+/// Infinite set of the terms of a linear recurrence, where each term is a fixed combination of
+/// the terms before it: a(n) = c1 * a(n - 1) + c2 * a(n - 2) + ... + ck * a(n - k), starting from
+/// the seeds a(0), ..., a(k - 1). For example, the coefficients [1, 1] with the seeds [1, 1] give
+/// the Fibonacci numbers, and [2, 1] with the seeds [1, 2] give the Pell numbers.
+///
+/// A term that repeats the one before it (as only the seeds can) is only yielded once, so the
+/// Fibonacci numbers go 1, 2, 3, 5, ...
+pub struct LinearRecurrence<T = u128> {
+    /// The coefficients of the previous term, the one before that, and so on.
+    coefficients: Vec<T>,
+
+    seeds: Vec<T>,
+
+    /// Up to the last `coefficients.len()` terms, oldest first, which the next term after
+    /// `next` is made from.
+    recent: VecDeque<T>,
+
+    /// The next term to return, or None once it no longer fits in T.
+    next: Option<T>,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element> LinearRecurrence<T> {
+    /// Creates the recurrence with the given coefficients, where `coefficients[0]` multiplies the
+    /// previous term, and seeds.
+    ///
+    /// Panics unless the recurrence keeps growing: there must be as many seeds as coefficients,
+    /// the seeds must be positive and in ascending order (repeats are allowed), the coefficients
+    /// must not be negative, the first coefficient must be at least 1 and the coefficients must
+    /// add up to at least 2. Then every term after the seeds is greater than the one before it.
+    pub fn new(coefficients: Vec<T>, seeds: Vec<T>) -> Self {
+        assert!(
+            !coefficients.is_empty() && coefficients.len() == seeds.len(),
+            "LinearRecurrence needs one seed for every coefficient"
+        );
+        assert!(
+            seeds[0] > T::zero() && seeds.windows(2).all(|pair| pair[0] <= pair[1]),
+            "the seeds of LinearRecurrence must be positive and ascending"
+        );
+
+        // each coefficient is capped at 2 in the sum, so that adding them up can't overflow
+        let capped_sum = coefficients
+            .iter()
+            .fold(T::zero(), |sum, c| sum + c.clone().min(T::two()));
+        assert!(
+            coefficients[0] >= T::one()
+                && coefficients.iter().all(|c| *c >= T::zero())
+                && capped_sum >= T::two(),
+            "the coefficients of LinearRecurrence must keep it growing"
+        );
+
+        Self {
+            recent: VecDeque::with_capacity(coefficients.len()),
+            next: Some(seeds[0].clone()),
+            coefficients,
+            seeds,
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+
+    pub fn coefficients(&self) -> &[T] {
+        &self.coefficients
+    }
+
+    pub fn seeds(&self) -> &[T] {
+        &self.seeds
+    }
+
+    /// Returns the term after the ones in `recent`, or None if it doesn't fit in T.
+    fn following(&self) -> Option<T> {
+        if self.recent.len() < self.seeds.len() {
+            return Some(self.seeds[self.recent.len()].clone());
+        }
+
+        self.coefficients
+            .iter()
+            .zip(self.recent.iter().rev())
+            .try_fold(T::zero(), |sum, (c, term)| {
+                sum.checked_add(&c.checked_mul(term)?)
+            })
+    }
+
+    /// Records that `term` has been reached, keeping only the terms the recurrence needs.
+    fn push(&mut self, term: T) {
+        if self.recent.len() == self.coefficients.len() {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(term);
+    }
+}
+
+impl<T: Element> InfiniteSet for LinearRecurrence<T> {
+    fn contains(&self, x: &T) -> bool {
+        // the terms grow exponentially, so walking a fresh copy of the recurrence up to x only
+        // takes a number of steps logarithmic in x
+        let mut terms = Self::new(self.coefficients.clone(), self.seeds.clone());
+        terms.find(|term| term >= x).is_some_and(|term| term == *x)
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        SetFacts {
+            lower_bound: Some(self.seeds[0].clone()),
+            ..SetFacts::default()
+        }
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        // there is one term in the gap between the last term and the next
+        let last = self.recent.back().and_then(T::to_f64);
+        let near = self.next.as_ref().and_then(T::to_f64);
+        match (last, near) {
+            (Some(last), Some(near)) => 1.0 / (near - last).max(1.0),
+            (None, Some(_)) => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+impl<T: Element> Iterator for LinearRecurrence<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("LinearRecurrence"),
+        };
+
+        // move on to the next term, skipping over repeats of the current one among the seeds
+        loop {
+            self.push(current.clone());
+            self.next = self.following();
+            if self.next.as_ref() != Some(&current) {
+                break;
+            }
+        }
+
+        Some(current)
+    }
+}
+
+/// Infinite set of the Fibonacci numbers (1, 2, 3, 5, 8, ...). This is `LinearRecurrence` with
+/// the coefficients [1, 1] and the seeds [1, 1], except that `contains` doesn't have to walk
+/// through the terms: x is a Fibonacci number exactly when 5x^2 + 4 or 5x^2 - 4 is a square.
+pub struct InfiniteFibonacci<T = u128> {
+    terms: LinearRecurrence<T>,
+}
+
+impl<T: Element> InfiniteFibonacci<T> {
+    pub fn new() -> Self {
+        Self {
+            terms: LinearRecurrence::new(vec![T::one(), T::one()], vec![T::one(), T::one()]),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(self, policy: OverflowPolicy) -> Self {
+        Self {
+            terms: self.terms.with_overflow_policy(policy),
+        }
+    }
+}
+
+impl<T: Element> Default for InfiniteFibonacci<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element> InfiniteSet for InfiniteFibonacci<T> {
+    fn contains(&self, x: &T) -> bool {
+        if *x <= T::zero() {
+            return false;
+        }
+
+        let four = T::two() * T::two();
+        let five_squared = x
+            .checked_mul(x)
+            .and_then(|square| square.checked_mul(&(four.clone() + T::one())));
+        match five_squared.and_then(|n| n.checked_add(&four)) {
+            Some(plus) => is_square(&plus) || is_square(&(plus - four.clone() - four)),
+            // 5x^2 + 4 doesn't fit in T, so walk through the terms instead
+            None => self.terms.contains(x),
+        }
+    }
+
+    fn facts(&self) -> SetFacts<T> {
+        self.terms.facts()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.terms.overflow()
+    }
+
+    fn estimated_density(&self) -> f64 {
+        self.terms.estimated_density()
+    }
+}
+
+impl<T: Element> Iterator for InfiniteFibonacci<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.terms.next()
+    }
+}
+
+/// Returns true if the non-negative `n` is a perfect square.
+fn is_square<T: Element>(n: &T) -> bool {
+    let root = roots::root(n, 2);
+    root.clone() * root == *n
+}
+
+/// Infinite set of all integers, in both directions
+pub struct InfiniteIntegers<T = i128> {
+    /// The next non-negative integer, or None once it no longer fits in T.
+    up: Option<T>,
+
+    /// The next negative integer, or None once it no longer fits in T.
+    down: Option<T>,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element + Signed> InfiniteIntegers<T> {
+    pub fn new() -> Self {
+        Self {
+            up: Some(T::zero()),
+            down: Some(-T::one()),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element + Signed> Default for InfiniteIntegers<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element + Signed> BidirectionalSet for InfiniteIntegers<T> {
+    type Item = T;
+
+    fn contains(&self, _x: &T) -> bool {
+        true
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn next_up(&mut self) -> Option<T> {
+        let current = match self.up.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteIntegers"),
+        };
+        self.up = current.checked_add(&T::one());
+        Some(current)
+    }
+
+    fn next_down(&mut self) -> Option<T> {
+        let current = match self.down.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteIntegers"),
+        };
+        self.down = current.checked_sub(&T::one());
+        Some(current)
+    }
+}
+
+/// Infinite set of all even integers, in both directions (includes zero)
+pub struct InfiniteSignedEvens<T = i128> {
+    /// The next non-negative even number, or None once it no longer fits in T.
+    up: Option<T>,
+
+    /// The next negative even number, or None once it no longer fits in T.
+    down: Option<T>,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element + Signed> InfiniteSignedEvens<T> {
+    pub fn new() -> Self {
+        Self {
+            up: Some(T::zero()),
+            down: Some(-T::two()),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element + Signed> Default for InfiniteSignedEvens<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element + Signed> BidirectionalSet for InfiniteSignedEvens<T> {
+    type Item = T;
+
+    fn contains(&self, x: &T) -> bool {
+        x.is_even()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn next_up(&mut self) -> Option<T> {
+        let current = match self.up.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteSignedEvens"),
+        };
+        self.up = current.checked_add(&T::two());
+        Some(current)
+    }
+
+    fn next_down(&mut self) -> Option<T> {
+        let current = match self.down.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteSignedEvens"),
+        };
+        self.down = current.checked_sub(&T::two());
+        Some(current)
+    }
+}
+
+/// Infinite set of all odd integers, in both directions
+pub struct InfiniteSignedOdds<T = i128> {
+    /// The next positive odd number, or None once it no longer fits in T.
+    up: Option<T>,
+
+    /// The next negative odd number, or None once it no longer fits in T.
+    down: Option<T>,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element + Signed> InfiniteSignedOdds<T> {
+    pub fn new() -> Self {
+        Self {
+            up: Some(T::one()),
+            down: Some(-T::one()),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element + Signed> Default for InfiniteSignedOdds<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element + Signed> BidirectionalSet for InfiniteSignedOdds<T> {
+    type Item = T;
+
+    fn contains(&self, x: &T) -> bool {
+        x.is_odd()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn next_up(&mut self) -> Option<T> {
+        let current = match self.up.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteSignedOdds"),
+        };
+        self.up = current.checked_add(&T::two());
+        Some(current)
+    }
+
+    fn next_down(&mut self) -> Option<T> {
+        let current = match self.down.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteSignedOdds"),
+        };
+        self.down = current.checked_sub(&T::two());
+        Some(current)
+    }
+}
+
+/// Infinite set of negative odd numbers. It has nothing at or above zero, so next_up() always
+/// returns None.
+pub struct InfiniteNegativeOdds<T = i128> {
+    /// The next negative odd number, or None once it no longer fits in T.
+    next: Option<T>,
+
+    overflow: OverflowGuard,
+}
+
+impl<T: Element + Signed> InfiniteNegativeOdds<T> {
+    pub fn new() -> Self {
+        Self {
+            next: Some(-T::one()),
+            overflow: OverflowGuard::default(),
+        }
+    }
+
+    /// Sets what happens once the next element would not fit in `T`. Defaults to
+    /// `OverflowPolicy::Stop`.
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow = OverflowGuard::new(policy);
+        self
+    }
+}
+
+impl<T: Element + Signed> Default for InfiniteNegativeOdds<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Element + Signed> BidirectionalSet for InfiniteNegativeOdds<T> {
+    type Item = T;
+
+    fn contains(&self, x: &T) -> bool {
+        x.is_negative() && x.is_odd()
+    }
+
+    fn overflow(&self) -> Option<OverflowError> {
+        self.overflow.error()
+    }
+
+    fn next_up(&mut self) -> Option<T> {
+        None
+    }
+
+    fn next_down(&mut self) -> Option<T> {
+        let current = match self.next.take() {
+            Some(current) => current,
+            None => return self.overflow.overflowed::<T>("InfiniteNegativeOdds"),
+        };
+        self.next = current.checked_sub(&T::two());
+        Some(current)
     }
 }
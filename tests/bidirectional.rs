@@ -0,0 +1,82 @@
+use infinite_sets::prelude::*;
+
+#[test]
+fn integers_go_both_ways_from_zero() {
+    let mut integers = InfiniteIntegers::new();
+
+    assert_eq!(integers.next_up(), Some(0));
+    assert_eq!(integers.next_up(), Some(1));
+    assert_eq!(integers.next_down(), Some(-1));
+    assert_eq!(integers.next_down(), Some(-2));
+}
+
+#[test]
+fn interleaving_reaches_both_directions() {
+    let integers: Vec<i128> = InfiniteIntegers::new().interleaved().take(5).collect();
+    assert_eq!(integers, vec![0, -1, 1, -2, 2]);
+
+    // with nothing upward, every turn goes downward
+    let negative_odds: Vec<i128> = InfiniteNegativeOdds::new().interleaved().take(3).collect();
+    assert_eq!(negative_odds, vec![-1, -3, -5]);
+}
+
+#[test]
+fn union_merges_each_direction_in_order() {
+    let mut union = InfiniteSignedEvens::new().union(InfiniteNegativeOdds::new());
+
+    let up: Vec<i128> = (0..3).filter_map(|_| union.next_up()).collect();
+    let down: Vec<i128> = (0..5).filter_map(|_| union.next_down()).collect();
+
+    assert_eq!(up, vec![0, 2, 4]);
+    assert_eq!(down, vec![-1, -2, -3, -4, -5]);
+    assert!(union.contains(&-3));
+    assert!(!union.contains(&3));
+}
+
+#[test]
+fn union_drops_shared_elements() {
+    let union: Vec<i128> = InfiniteSignedOdds::new()
+        .union(InfiniteNegativeOdds::new())
+        .interleaved()
+        .take(4)
+        .collect();
+
+    assert_eq!(union, vec![1, -1, 3, -3]);
+}
+
+#[test]
+fn intersection_filters_each_direction() {
+    let mut intersection = InfiniteNegativeOdds::new().intersect(InfiniteSignedOdds::new());
+
+    assert_eq!(intersection.next_up(), None);
+    assert_eq!(intersection.next_down(), Some(-1));
+    assert_eq!(intersection.next_down(), Some(-3));
+    assert!(!intersection.contains(&1));
+}
+
+#[test]
+fn intersection_ends_where_a_set_has_nothing() {
+    let mut intersection = InfiniteSignedOdds::new().intersect(InfiniteNegativeOdds::new());
+
+    assert_eq!(intersection.next_up(), None);
+    assert_eq!(intersection.next_down(), Some(-1));
+    assert_eq!(intersection.next_down(), Some(-3));
+}
+
+#[test]
+fn bounded_search_gives_up_on_a_starving_direction() {
+    let mut intersection = InfiniteSignedEvens::<i128>::new()
+        .intersect(InfiniteSignedOdds::new())
+        .with_budget(100);
+
+    assert_eq!(intersection.next_up(), None);
+    assert!(intersection.try_next_down_within(10).is_err());
+}
+
+#[test]
+fn bidirectional_sets_can_be_made_of_other_signed_types() {
+    let odds: Vec<i32> = InfiniteSignedOdds::new().interleaved().take(4).collect();
+
+    assert_eq!(odds, vec![1, -1, 3, -3]);
+    assert!(InfiniteNegativeOdds::<i64>::new().contains(&-7));
+}
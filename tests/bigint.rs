@@ -0,0 +1,188 @@
+#![cfg(feature = "bigint")]
+
+use infinite_sets::prelude::*;
+use infinite_sets::BigUint;
+use num_traits::Num;
+
+fn big(s: &str) -> BigUint {
+    BigUint::from_str_radix(s, 10).unwrap()
+}
+
+#[test]
+fn arithmetic_matches_primitives() {
+    let a = big("340282366920938463463374607431768211455"); // u128::MAX
+    let b = BigUint::from(0xdead_beef_u64);
+
+    assert_eq!((a.clone() + b.clone()) - b.clone(), a);
+    assert_eq!(
+        (a.clone() * b.clone()) / b.clone(),
+        a,
+        "multiplying then dividing should round trip"
+    );
+    assert_eq!(
+        (a.clone() % b.clone()),
+        BigUint::from(u128::MAX % 0xdead_beef)
+    );
+    assert_eq!(a.to_string(), u128::MAX.to_string());
+}
+
+#[test]
+fn two_powers_keep_going_past_u128() {
+    let power = InfiniteTwoPowers::<BigUint>::new().nth(200).unwrap();
+
+    assert_eq!(power.bits(), 201);
+    assert_eq!(
+        power.to_string(),
+        "1606938044258990275541962092341162602522202993782792835301376"
+    );
+}
+
+#[test]
+fn factorials_keep_going_past_u128() {
+    let fifty = InfiniteFactorials::<BigUint>::new().nth(49).unwrap();
+
+    assert_eq!(
+        fifty,
+        big("30414093201713378043612608166064768844377641568960512000000000000")
+    );
+    assert!(InfiniteFactorials::new().contains(&fifty));
+    assert!(!InfiniteFactorials::new().contains(&(fifty + BigUint::from(1u64))));
+}
+
+#[test]
+fn big_sets_combine() {
+    let union: Vec<BigUint> = InfiniteTwoPowers::new()
+        .union(InfiniteFactorials::new())
+        .take(6)
+        .collect();
+
+    let expected: Vec<BigUint> = [1u64, 2, 4, 6, 8, 16]
+        .iter()
+        .map(|n| BigUint::from(*n))
+        .collect();
+    assert_eq!(union, expected);
+}
+
+#[test]
+fn primes_past_u128_are_recognized() {
+    let primes = InfinitePrimes::<BigUint>::new();
+
+    // the first prime past 2^130
+    assert!(primes.contains(&big("1361129467683753853853498429727072845993")));
+    // the product of the first primes past 2^64 and 2^66
+    assert!(!primes.contains(&big("1361129467683753854978749818223355494517")));
+}
+
+#[test]
+fn primes_end_at_u128_max_without_overflowing() {
+    let mut primes = InfinitePrimes::<BigUint>::new();
+
+    // the last prime before 2^128 is the last one the sieve generates
+    let last = BigUint::from(u128::MAX - 158);
+    assert_eq!(primes.seek(&BigUint::from(u128::MAX - 170)), Some(last));
+    assert_eq!(primes.next(), None);
+    assert_eq!(primes.overflow(), None);
+}
+
+#[test]
+fn fibonacci_keeps_going_past_u128() {
+    let fibonacci = InfiniteFibonacci::<BigUint>::new();
+    let x = InfiniteFibonacci::<BigUint>::new().nth(300).unwrap();
+
+    // F(302), far past the Fibonacci numbers that fit in a u128
+    assert_eq!(
+        x.to_string(),
+        "581811569836004006491505558634099066259034153405766997246569401"
+    );
+    assert!(fibonacci.contains(&x));
+    assert!(!fibonacci.contains(&(x + BigUint::from(1u64))));
+}
+
+#[test]
+fn squares_keep_going_past_u128() {
+    let root = big("340282366920938463463374607431768211457"); // u128::MAX + 2
+    let square = root.clone() * root.clone();
+    let squares = InfiniteSquares::<BigUint>::new();
+
+    assert!(squares.contains(&square));
+    assert!(!squares.contains(&(square.clone() - BigUint::from(1u64))));
+    assert_eq!(
+        InfiniteSquares::<BigUint>::new().seek(&(square.clone() - root)),
+        Some(square)
+    );
+}
+
+#[test]
+fn periodic_sets_of_biguint_dont_collapse_at_u128_max() {
+    let bound = BigUint::from(u128::MAX) * BigUint::from(4u64);
+    let mut union = InfiniteEvens::<BigUint>::new().union(InfiniteOdds::new());
+
+    assert!(union.as_periodic().is_none());
+    assert_eq!(union.seek(&bound), Some(bound.clone()));
+    assert_eq!(union.overflow(), None);
+
+    let mut intersection = InfiniteOdds::<BigUint>::new().intersect(ArithmeticProgression::new(
+        BigUint::from(3u64),
+        BigUint::from(3u64),
+    ));
+    let odd_multiple = bound + BigUint::from(3u64);
+    assert_eq!(intersection.seek(&odd_multiple), Some(odd_multiple));
+}
+
+#[test]
+fn division_of_many_digit_numbers_round_trips() {
+    // numbers of all sorts of lengths, built up from a simple pseudorandom sequence
+    let mut state = 0x2545_f491_4f6c_dd1d_u128;
+    let mut random = || {
+        state = state
+            .wrapping_mul(0x5851_f42d_4c95_7f2d_1405_7b7e_f767_814f)
+            .wrapping_add(1);
+        BigUint::from(state >> 32)
+    };
+    let mut numbers = vec![BigUint::from(1u64)];
+    for i in 0..40 {
+        let next = numbers[i].clone() * random() + random();
+        numbers.push(next);
+    }
+
+    for dividend in &numbers {
+        for divisor in &numbers {
+            let quotient = dividend.clone() / divisor.clone();
+            let remainder = dividend.clone() % divisor.clone();
+
+            assert!(remainder < *divisor);
+            assert_eq!(quotient * divisor.clone() + remainder, *dividend);
+        }
+    }
+
+    // divisors whose top digit makes the first estimate of each quotient digit too large
+    let top = BigUint::from(u128::MAX);
+    let divisor = top.clone() * top.clone() - BigUint::from(1u64);
+    let dividend = divisor.clone() * top.clone() + top.clone();
+    assert_eq!(dividend.clone() / divisor.clone(), top);
+    assert_eq!(dividend % divisor, top);
+
+    // a quotient digit whose estimate is still too large after correcting it, so the divisor
+    // has to be added back
+    let dividend = BigUint::from_str_radix("7fffffff800000000000000000000000", 16).unwrap();
+    let divisor = BigUint::from_str_radix("800000000000000000000001", 16).unwrap();
+    assert_eq!(dividend.clone() / divisor.clone(), big("4294967294"));
+    assert_eq!(dividend % divisor, big("39614081257132168792477007874"));
+}
+
+#[test]
+fn primality_of_a_few_thousand_bits_is_quick() {
+    let started = std::time::Instant::now();
+
+    // 2^4000 + 1 is divisible by 2^32 + 1 = 641 * 6700417 but by none of the primes up to 41,
+    // so it takes modular exponentiation to reject it. dividing a bit at a time instead of a
+    // digit at a time made this about 15 times slower
+    let x = (0..4000).fold(BigUint::from(1u64), |x, _| x.clone() + x) + BigUint::from(1u64);
+    assert!(!InfinitePrimes::<BigUint>::new().contains(&x));
+
+    assert!(
+        started.elapsed().as_secs() < 20,
+        "took {:?}",
+        started.elapsed()
+    );
+}
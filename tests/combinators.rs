@@ -0,0 +1,320 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use infinite_sets::prelude::*;
+use infinite_sets::SearchExhausted;
+
+/// The odd numbers, counting every element that is pulled from them.
+struct CountedOdds {
+    odds: InfiniteOdds,
+    pulled: Rc<Cell<usize>>,
+}
+
+impl CountedOdds {
+    fn new(pulled: &Rc<Cell<usize>>) -> Self {
+        Self {
+            odds: InfiniteOdds::new(),
+            pulled: Rc::clone(pulled),
+        }
+    }
+}
+
+impl InfiniteSet for CountedOdds {
+    fn contains(&self, x: &u128) -> bool {
+        self.odds.contains(x)
+    }
+}
+
+impl Iterator for CountedOdds {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        self.pulled.set(self.pulled.get() + 1);
+        self.odds.next()
+    }
+}
+
+/// The odd numbers, each yielded twice.
+struct TwiceOdds {
+    odds: InfiniteOdds,
+    repeat: Option<u128>,
+}
+
+impl TwiceOdds {
+    fn new() -> Self {
+        Self {
+            odds: InfiniteOdds::new(),
+            repeat: None,
+        }
+    }
+}
+
+impl InfiniteSet for TwiceOdds {
+    fn contains(&self, x: &u128) -> bool {
+        self.odds.contains(x)
+    }
+}
+
+impl Iterator for TwiceOdds {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        self.repeat.take().or_else(|| {
+            self.repeat = self.odds.next();
+            self.repeat
+        })
+    }
+}
+
+#[test]
+fn union_merges_in_ascending_order() {
+    let union: Vec<u128> = InfiniteEvens::new()
+        .union(InfiniteOdds::new())
+        .take(10)
+        .collect();
+
+    assert_eq!(union, (1..=10).collect::<Vec<u128>>());
+}
+
+#[test]
+fn union_drops_shared_elements() {
+    let union: Vec<u128> = InfiniteTwoPowers::new()
+        .union(InfiniteEvens::new())
+        .take(6)
+        .collect();
+
+    assert_eq!(union, vec![1, 2, 4, 6, 8, 10]);
+}
+
+#[test]
+fn union_drops_values_a_set_repeats() {
+    let fibonacci_or_lucas: Vec<u128> = InfiniteFibonacci::new()
+        .union(LinearRecurrence::new(vec![1, 1], vec![1, 3]))
+        .take(10)
+        .collect();
+    assert_eq!(fibonacci_or_lucas, vec![1, 2, 3, 4, 5, 7, 8, 11, 13, 18]);
+
+    let boxed: Vec<u128> = TwiceOdds::new()
+        .union(InfiniteTwoPowers::new())
+        .take(6)
+        .collect();
+    let composed: Vec<u128> = Union::new(TwiceOdds::new(), InfiniteTwoPowers::new())
+        .take(6)
+        .collect();
+    assert_eq!(boxed, vec![1, 2, 3, 4, 5, 7]);
+    assert_eq!(composed, boxed);
+}
+
+#[test]
+fn union_contains_either_operand() {
+    let union = InfinitePrimes::new().union(InfiniteEvens::new());
+
+    assert!(union.contains(&2));
+    assert!(union.contains(&7));
+    assert!(union.contains(&10));
+    assert!(!union.contains(&9));
+}
+
+#[test]
+fn intersection_keeps_shared_elements() {
+    let intersection: Vec<u128> = InfinitePrimes::new()
+        .intersect(InfiniteOdds::new())
+        .take(5)
+        .collect();
+
+    assert_eq!(intersection, vec![3, 5, 7, 11, 13]);
+}
+
+#[test]
+fn intersection_contains_both_operands() {
+    let intersection = InfinitePrimes::new().intersect(InfiniteOdds::new());
+
+    assert!(intersection.contains(&3));
+    assert!(!intersection.contains(&2));
+    assert!(!intersection.contains(&9));
+}
+
+#[test]
+fn union_does_no_work_until_iterated() {
+    let pulled = Rc::new(Cell::new(0));
+
+    let mut union = InfiniteUnion::from_many((0..1000).map(|_| CountedOdds::new(&pulled)))
+        .union(EmptySet::new())
+        .symmetric_difference(CountedOdds::new(&pulled).union(InfiniteEvens::new()));
+    assert_eq!(pulled.get(), 0);
+
+    // the odd numbers cancel out, leaving the evens
+    assert_eq!(union.next(), Some(2));
+    assert!(pulled.get() > 0);
+}
+
+#[test]
+fn union_of_many_sets_merges_them_all() {
+    let powers = InfiniteUnion::from_many((2..=31).map(InfinitePowers::<u128>::new));
+
+    let mut expected: Vec<u128> = (2..=31u128)
+        .flat_map(|base| (0..8).map(move |k| base.pow(k)))
+        .filter(|x| *x <= 1000)
+        .collect();
+    expected.sort_unstable();
+    expected.dedup();
+
+    let union: Vec<u128> = powers.take_while(|x| *x <= 1000).collect();
+    assert_eq!(union, expected);
+}
+
+#[test]
+fn intersection_of_many_sets_keeps_what_they_all_share() {
+    let sets: Vec<Box<dyn InfiniteSet<Item = u128>>> = vec![
+        Box::new(InfiniteOdds::new()),
+        Box::new(InfinitePrimes::new()),
+        Box::new(ArithmeticProgression::new(1, 4)),
+    ];
+    let intersection = InfiniteIntersection::from_many(sets);
+
+    assert!(intersection.contains(&13));
+    assert!(!intersection.contains(&7));
+    assert_eq!(
+        intersection.take(5).collect::<Vec<_>>(),
+        vec![5, 13, 17, 29, 37]
+    );
+}
+
+#[test]
+fn intersection_of_dozens_of_sets_leaps_through_them_all() {
+    let sets = (0..24).map(|i| -> Box<dyn InfiniteSet<Item = u128>> {
+        if i % 3 == 0 {
+            Box::new(InfinitePrimes::new())
+        } else {
+            Box::new(InfiniteOdds::new())
+        }
+    });
+    let odd_primes = InfiniteIntersection::from_many(sets);
+
+    assert_eq!(
+        odd_primes.take(6).collect::<Vec<_>>(),
+        vec![3, 5, 7, 11, 13, 17]
+    );
+}
+
+#[test]
+fn intersection_of_no_sets_is_empty() {
+    let mut nothing = InfiniteIntersection::<u128>::from_many(Vec::<InfiniteOdds>::new());
+
+    assert!(nothing.is_known_empty());
+    assert!(!nothing.contains(&1));
+    assert_eq!(nothing.next(), None);
+}
+
+#[test]
+fn difference_removes_the_second_set() {
+    let odd_composites: Vec<u128> = InfiniteOdds::new()
+        .difference(InfinitePrimes::new())
+        .take(5)
+        .collect();
+
+    assert_eq!(odd_composites, vec![1, 9, 15, 21, 25]);
+}
+
+#[test]
+fn difference_contains_only_the_first_set() {
+    let difference = InfiniteOdds::new().difference(InfinitePrimes::new());
+
+    assert!(difference.contains(&9));
+    assert!(!difference.contains(&7));
+    assert!(!difference.contains(&10));
+}
+
+#[test]
+fn complement_walks_the_universe() {
+    let not_powers_of_two: Vec<u128> = InfiniteTwoPowers::new()
+        .complement_in(InfinitePositiveInts::new())
+        .take(6)
+        .collect();
+
+    assert_eq!(not_powers_of_two, vec![3, 5, 6, 7, 9, 10]);
+}
+
+#[test]
+fn complement_contains_what_the_set_does_not() {
+    let complement = InfiniteTwoPowers::new().complement_in(InfinitePositiveInts::new());
+
+    assert!(complement.contains(&12));
+    assert!(!complement.contains(&16));
+    // nothing outside the universe is in the complement
+    assert!(!complement.contains(&0));
+}
+
+#[test]
+fn symmetric_difference_drops_shared_elements() {
+    let disagreements: Vec<u128> = InfinitePrimes::new()
+        .symmetric_difference(InfiniteOdds::new())
+        .take(6)
+        .collect();
+
+    assert_eq!(disagreements, vec![1, 2, 9, 15, 21, 25]);
+}
+
+#[test]
+fn symmetric_difference_drops_values_a_set_repeats() {
+    let disagreements: Vec<u128> = TwiceOdds::new()
+        .symmetric_difference(InfiniteEvens::new())
+        .take(6)
+        .collect();
+    assert_eq!(disagreements, vec![1, 2, 3, 4, 5, 6]);
+
+    // the repeated odd numbers below 10 cancel out against the odd numbers in the range
+    let small_odds = InfiniteOdds::new().intersect(FiniteRange::new(0, 10));
+    let disagreements: Vec<u128> = TwiceOdds::new()
+        .symmetric_difference(small_odds)
+        .take(3)
+        .collect();
+    assert_eq!(disagreements, vec![11, 13, 15]);
+}
+
+#[test]
+fn symmetric_difference_contains_exactly_one_operand() {
+    let disagreements = InfinitePrimes::new().symmetric_difference(InfiniteOdds::new());
+
+    assert!(disagreements.contains(&2));
+    assert!(disagreements.contains(&9));
+    assert!(!disagreements.contains(&7));
+    assert!(!disagreements.contains(&4));
+}
+
+#[test]
+fn bounded_search_gives_up_on_starving_intersection() {
+    let mut even_primes = InfiniteEvens::new().intersect(InfinitePrimes::new());
+
+    assert_eq!(even_primes.try_next_within(100), Ok(Some(2)));
+    assert_eq!(
+        even_primes.try_next_within(100),
+        Err(SearchExhausted { candidates: 100 })
+    );
+}
+
+#[test]
+fn bounded_search_resumes_where_it_left_off() {
+    let mut odd_primes = InfiniteOdds::new().intersect(InfinitePrimes::new());
+
+    // 1 isn't prime, so one candidate isn't enough to find 3
+    assert!(odd_primes.try_next_within(1).is_err());
+    assert_eq!(odd_primes.try_next_within(1), Ok(Some(3)));
+}
+
+#[test]
+fn budget_ends_iteration_instead_of_stalling() {
+    let even_primes: Vec<u128> = InfiniteEvens::new()
+        .intersect(InfinitePrimes::new())
+        .with_budget(100)
+        .take(10)
+        .collect();
+    assert_eq!(even_primes, vec![2]);
+
+    let evens: Vec<u128> = InfinitePositiveInts::new()
+        .difference(InfiniteOdds::new())
+        .with_budget(2)
+        .take(3)
+        .collect();
+    assert_eq!(evens, vec![2, 4, 6]);
+}
@@ -0,0 +1,173 @@
+use infinite_sets::prelude::*;
+use infinite_sets::SearchExhausted;
+
+/// The positive integers whose residue modulo the length of a borrowed table is marked in it.
+struct Marked<'a> {
+    table: &'a [bool],
+    ints: InfinitePositiveInts,
+}
+
+impl<'a> Marked<'a> {
+    fn new(table: &'a [bool]) -> Self {
+        Self {
+            table,
+            ints: InfinitePositiveInts::new(),
+        }
+    }
+}
+
+impl InfiniteSet for Marked<'_> {
+    fn contains(&self, x: &u128) -> bool {
+        *x > 0 && self.table[(x % self.table.len() as u128) as usize]
+    }
+}
+
+impl Iterator for Marked<'_> {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        let table = self.table;
+        self.ints
+            .find(|x| table[(x % table.len() as u128) as usize])
+    }
+}
+
+#[test]
+fn union_of_borrowed_sets() {
+    // 1 and 4 mod 5
+    let table = vec![false, true, false, false, true];
+    let union = Union::new(Marked::new(&table), InfiniteEvens::new());
+
+    assert!(union.contains(&9));
+    assert!(!union.contains(&3));
+    assert_eq!(
+        union.take(8).collect::<Vec<_>>(),
+        vec![1, 2, 4, 6, 8, 9, 10, 11]
+    );
+}
+
+#[test]
+fn intersection_of_borrowed_sets() {
+    let table = vec![false, true, false, false, true];
+    let intersection = Intersection::new(Marked::new(&table), InfinitePrimes::new());
+
+    assert!(intersection.contains(&11));
+    assert!(!intersection.contains(&9));
+    assert_eq!(
+        intersection.take(5).collect::<Vec<_>>(),
+        vec![11, 19, 29, 31, 41]
+    );
+}
+
+#[test]
+fn composed_sets_nest_and_agree_with_the_boxed_combinators() {
+    let composed = Intersection::new(
+        Union::new(InfiniteTwoPowers::<u128>::new(), InfiniteOdds::new()),
+        InfinitePrimes::new(),
+    );
+    let boxed = InfiniteTwoPowers::<u128>::new()
+        .union(InfiniteOdds::new())
+        .intersect(InfinitePrimes::new());
+
+    assert_eq!(
+        composed.take(20).collect::<Vec<_>>(),
+        boxed.take(20).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn composed_intersection_is_known_empty() {
+    let mut never = Intersection::new(InfiniteEvens::<u128>::new(), InfiniteOdds::new());
+
+    assert!(never.is_known_empty());
+    assert_eq!(never.next(), None);
+    assert_eq!(never.try_next_within(0), Ok(None));
+}
+
+#[test]
+fn bounded_search_gives_up_on_a_starving_composed_intersection() {
+    let mut even_primes = Intersection::new(InfiniteEvens::<u128>::new(), InfinitePrimes::new());
+
+    assert_eq!(even_primes.try_next_within(100), Ok(Some(2)));
+    assert_eq!(
+        even_primes.try_next_within(100),
+        Err(SearchExhausted { candidates: 100 })
+    );
+
+    let even_primes: Vec<u128> =
+        Intersection::new(InfiniteEvens::<u128>::new(), InfinitePrimes::new())
+            .with_budget(100)
+            .take(10)
+            .collect();
+    assert_eq!(even_primes, vec![2]);
+}
+
+#[test]
+fn monotone_images_keep_their_order() {
+    let odd_squares = InfiniteOdds::<u64>::new().map_monotone(|x| x * x, |y| Some(y.isqrt()));
+
+    assert!(odd_squares.contains(&81));
+    assert!(!odd_squares.contains(&64));
+    assert!(!odd_squares.contains(&80));
+    assert_eq!(
+        odd_squares.take(5).collect::<Vec<_>>(),
+        vec![1, 9, 25, 49, 81]
+    );
+}
+
+#[test]
+fn monotone_images_can_search_their_source() {
+    // 2p + 1 for every prime p, with an inverse that walks the primes
+    let shifted = InfinitePrimes::<u64>::new().map_monotone(
+        |p| 2 * p + 1,
+        |y| InfinitePrimes::new().find(|p| 2 * p + 1 >= *y),
+    );
+
+    assert!(shifted.contains(&23));
+    assert!(!shifted.contains(&19));
+    assert_eq!(shifted.facts().lower_bound, Some(5));
+    assert_eq!(shifted.take(4).collect::<Vec<_>>(), vec![5, 7, 11, 15]);
+}
+
+#[test]
+fn monotone_images_without_an_inverse_search_a_fresh_source() {
+    let shifted = MonotoneImage::searching(InfinitePrimes::<u64>::new, |p| 2 * p + 1);
+
+    assert!(shifted.contains(&23));
+    assert!(!shifted.contains(&19));
+    assert!(!shifted.contains(&3));
+    // 1000003 is prime, and the search only takes a few dozen seeks to find it
+    assert!(shifted.contains(&2_000_007));
+    assert!(!shifted.contains(&2_000_009));
+    assert_eq!(shifted.take(4).collect::<Vec<_>>(), vec![5, 7, 11, 15]);
+
+    let mut shifted = MonotoneImage::searching(InfinitePrimes::<u64>::new, |p| 2 * p + 1);
+    assert_eq!(shifted.seek(&16), Some(23));
+    assert_eq!(shifted.next(), Some(27));
+}
+
+#[test]
+fn monotone_images_seek_through_their_inverse() {
+    let mut odd_squares = InfiniteOdds::<u64>::new().map_monotone(|x| x * x, |y| Some(y.isqrt()));
+
+    assert_eq!(odd_squares.seek(&50), Some(81));
+    assert_eq!(odd_squares.seek(&10_001), Some(10_201));
+    assert_eq!(odd_squares.next(), Some(10_609));
+
+    // an inverse that can't place the bound leaves the image to walk its source
+    let mut walked = InfiniteOdds::<u64>::new().map_monotone(|x| x * x, |_| None);
+    assert_eq!(walked.seek(&50), Some(81));
+}
+
+#[test]
+fn monotone_images_combine_with_other_sets() {
+    let odd_squares = InfiniteOdds::<u64>::new().map_monotone(|x| x * x, |y| Some(y.isqrt()));
+    let even_squares = InfiniteEvens::<u64>::new().map_monotone(|x| x * x, |y| Some(y.isqrt()));
+    let squares = odd_squares.union(even_squares);
+
+    assert!(squares.contains(&36));
+    assert_eq!(
+        squares.take(6).collect::<Vec<_>>(),
+        InfiniteSquares::<u64>::new().take(6).collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use infinite_sets::prelude::*;
+
+#[test]
+fn sets_estimate_their_density() {
+    assert_eq!(InfiniteEvens::<u128>::new().estimated_density(), 0.5);
+    assert_eq!(
+        ArithmeticProgression::<u128>::new(3, 8).estimated_density(),
+        0.125
+    );
+    assert_eq!(
+        PeriodicSet::<u128>::new(6, vec![1, 5]).estimated_density(),
+        1.0 / 3.0
+    );
+    assert_eq!(EmptySet::<u128>::new().estimated_density(), 0.0);
+
+    // the density of the primes near n is about 1 / ln(n)
+    let mut primes = InfinitePrimes::<u128>::new();
+    primes.seek(&1_000_000);
+    let expected = 1.0 / 1_000_000f64.ln();
+    assert!((primes.estimated_density() - expected).abs() < 0.001);
+
+    // powers thin out as they go
+    let mut powers = InfiniteTwoPowers::<u128>::new();
+    let near_one = powers.estimated_density();
+    powers.seek(&1024);
+    assert!(powers.estimated_density() < near_one / 1000.0);
+}
+
+#[test]
+fn combinators_estimate_their_density() {
+    let union = InfiniteEvens::<u128>::new().union(InfinitePrimes::new());
+    assert_eq!(union.estimated_density(), 1.0);
+
+    let never = InfiniteEvens::<u128>::new().intersect(InfiniteOdds::new());
+    assert_eq!(never.estimated_density(), 0.0);
+
+    let evens_not_by_four =
+        InfiniteEvens::<u128>::new().difference(ArithmeticProgression::new(5, 4));
+    assert_eq!(evens_not_by_four.estimated_density(), 0.375);
+}
+
+/// A dense set that counts how many elements are taken from it one at a time, as opposed to
+/// skipped over with seek.
+struct Walked {
+    ints: InfinitePositiveInts,
+    walked: Rc<Cell<usize>>,
+}
+
+impl InfiniteSet for Walked {
+    fn contains(&self, x: &u128) -> bool {
+        self.ints.contains(x)
+    }
+
+    fn seek(&mut self, lower_bound: &u128) -> Option<u128> {
+        self.ints.seek(lower_bound)
+    }
+}
+
+impl Iterator for Walked {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        self.walked.set(self.walked.get() + 1);
+        self.ints.next()
+    }
+}
+
+#[test]
+fn intersection_advances_its_sparsest_set() {
+    let walked = Rc::new(Cell::new(0));
+    let ints = || Walked {
+        ints: InfinitePositiveInts::new(),
+        walked: Rc::clone(&walked),
+    };
+
+    // whichever side the dense set is on, the powers of three drive the intersection
+    let powers: Vec<u128> = ints().intersect(InfinitePowers::new(3)).take(40).collect();
+    assert_eq!(powers.last(), Some(&3u128.pow(39)));
+    let powers: Vec<u128> = InfinitePowers::new(3).intersect(ints()).take(40).collect();
+    assert_eq!(powers.last(), Some(&3u128.pow(39)));
+
+    assert!(walked.get() <= 2);
+}
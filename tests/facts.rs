@@ -0,0 +1,60 @@
+use infinite_sets::prelude::*;
+use infinite_sets::Residue;
+
+#[test]
+fn residues_prove_disjointness() {
+    assert!(Residue::new(2, 0).is_disjoint(&Residue::new(2, 1)));
+    assert!(Residue::new(4, 1).is_disjoint(&Residue::new(6, 4)));
+    assert!(!Residue::new(4, 1).is_disjoint(&Residue::new(6, 3)));
+}
+
+#[test]
+fn residues_join_to_a_shared_class() {
+    assert_eq!(
+        Residue::new(4, 1).join(&Residue::new(6, 3)),
+        Some(Residue::new(2, 1))
+    );
+    assert_eq!(Residue::new(2, 0).join(&Residue::new(2, 1)), None);
+}
+
+#[test]
+fn intersection_of_evens_and_odds_is_known_empty() {
+    let mut never = InfiniteEvens::new().intersect(InfiniteOdds::new());
+
+    assert!(never.is_known_empty());
+    assert!(never.facts().empty);
+    assert_eq!(never.next(), None);
+    assert!(!never.contains(&2));
+}
+
+#[test]
+fn nested_intersections_are_known_empty() {
+    let odd_primes = InfiniteOdds::<u128>::new().intersect(InfinitePrimes::new());
+    let never = InfiniteEvens::new().intersect(odd_primes);
+
+    assert!(never.is_known_empty());
+}
+
+#[test]
+fn intersection_without_proof_is_not_known_empty() {
+    let even_primes = InfiniteEvens::<u128>::new().intersect(InfinitePrimes::new());
+
+    assert!(!even_primes.is_known_empty());
+}
+
+#[test]
+fn union_facts_keep_shared_structure() {
+    let facts = InfiniteEvens::new().union(InfiniteOdds::new()).facts();
+
+    assert_eq!(facts.residue, None);
+    assert_eq!(facts.lower_bound, Some(1));
+}
+
+#[test]
+fn powers_of_odd_bases_are_disjoint_from_evens() {
+    let facts = InfinitePowers::<u128>::new(3).facts();
+    assert_eq!(facts.residue, Some(Residue::new(2, 1)));
+
+    let never = InfinitePowers::<u128>::new(3).intersect(InfiniteEvens::new());
+    assert!(never.is_known_empty());
+}
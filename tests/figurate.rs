@@ -0,0 +1,107 @@
+use infinite_sets::prelude::*;
+
+/// Checks `contains` and `seek` of a fresh set from `make` against walking through it.
+fn check_against_walking<S: InfiniteSet<Item = u64>>(make: impl Fn() -> S, limit: u64) {
+    let walked: Vec<u64> = make().take_while(|x| *x <= limit).collect();
+    let set = make();
+
+    for x in 0..=limit {
+        assert_eq!(set.contains(&x), walked.contains(&x), "contains({})", x);
+        assert_eq!(
+            make().seek(&x),
+            make().find(|element| *element >= x),
+            "seek({})",
+            x
+        );
+    }
+}
+
+#[test]
+fn powers_are_listed_in_order() {
+    let squares: Vec<u32> = InfiniteSquares::new().take(5).collect();
+    assert_eq!(squares, vec![1, 4, 9, 16, 25]);
+
+    let cubes: Vec<u32> = InfiniteCubes::new().take(5).collect();
+    assert_eq!(cubes, vec![1, 8, 27, 64, 125]);
+
+    let fifths: Vec<u64> = InfiniteKthPowers::new(5).take(4).collect();
+    assert_eq!(fifths, vec![1, 32, 243, 1024]);
+}
+
+#[test]
+fn polygonal_and_pyramidal_numbers_are_listed_in_order() {
+    let triangular: Vec<u32> = InfiniteTriangularNumbers::new().take(5).collect();
+    assert_eq!(triangular, vec![1, 3, 6, 10, 15]);
+
+    let pentagonal: Vec<u32> = InfinitePolygonalNumbers::new(5).take(5).collect();
+    assert_eq!(pentagonal, vec![1, 5, 12, 22, 35]);
+
+    let tetrahedral: Vec<u32> = InfinitePyramidalNumbers::new(3).take(5).collect();
+    assert_eq!(tetrahedral, vec![1, 4, 10, 20, 35]);
+
+    let square_pyramidal: Vec<u32> = InfinitePyramidalNumbers::new(4).take(5).collect();
+    assert_eq!(square_pyramidal, vec![1, 5, 14, 30, 55]);
+}
+
+#[test]
+fn contains_and_seek_agree_with_walking() {
+    for exponent in 2..=5 {
+        check_against_walking(|| InfiniteKthPowers::new(exponent), 2000);
+    }
+    for sides in 3..=8 {
+        check_against_walking(|| InfinitePolygonalNumbers::new(sides), 2000);
+        check_against_walking(|| InfinitePyramidalNumbers::new(sides), 2000);
+    }
+}
+
+#[test]
+fn figurate_numbers_reach_the_end_of_their_element_type() {
+    let largest = u128::from(u64::MAX);
+    let squares = InfiniteSquares::<u128>::new();
+    assert!(squares.contains(&(largest * largest)));
+    assert!(!squares.contains(&(largest * largest - 1)));
+    assert!(!squares.contains(&(largest * largest + 1)));
+
+    let mut squares = InfiniteSquares::<u128>::new();
+    assert_eq!(
+        squares.seek(&(largest * largest - 1)),
+        Some(largest * largest)
+    );
+    assert_eq!(squares.next(), None);
+    assert!(squares.overflow().is_some());
+
+    // the triangular number 2^32(2^32 + 1)/2 is the first one past 2^63
+    let mut triangular = InfiniteTriangularNumbers::<u64>::new();
+    assert_eq!(
+        triangular.seek(&(1 << 63)),
+        Some((1 << 31) * ((1 << 32) + 1))
+    );
+    // and this is the last one that fits in a u64
+    assert_eq!(
+        triangular.seek(&18446744070963499000),
+        Some(18446744070963499500)
+    );
+    assert_eq!(triangular.next(), None);
+}
+
+#[test]
+fn figurate_numbers_of_signed_types_are_positive() {
+    let squares = InfiniteSquares::<i64>::new();
+
+    assert!(squares.contains(&49));
+    assert!(!squares.contains(&-49));
+    assert!(!squares.contains(&0));
+    assert_eq!(InfiniteCubes::<i64>::new().seek(&-8), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "must be at least 2")]
+fn powers_need_an_exponent_of_at_least_two() {
+    InfiniteKthPowers::<u32>::new(1);
+}
+
+#[test]
+#[should_panic(expected = "at least 3 sides")]
+fn polygons_need_at_least_three_sides() {
+    InfinitePolygonalNumbers::<u32>::new(2);
+}
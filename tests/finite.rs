@@ -0,0 +1,88 @@
+use std::collections::BTreeSet;
+
+use infinite_sets::prelude::*;
+
+#[test]
+fn finite_sets_yield_their_elements_in_order() {
+    let mut set: FiniteSet<u32> = vec![9, 3, 7, 3, 1].into_iter().collect();
+
+    assert_eq!(set.len(), 4);
+    assert_eq!(set.by_ref().collect::<Vec<_>>(), vec![1, 3, 7, 9]);
+    assert_eq!(set.next(), None);
+    assert!(set.contains(&3));
+    assert!(!set.contains(&4));
+}
+
+#[test]
+fn finite_sets_seek_past_missing_elements() {
+    let mut set = FiniteSet::from((1..=50).map(|x| x * 10).collect::<BTreeSet<u32>>());
+
+    assert_eq!(set.seek(&101), Some(110));
+    assert_eq!(set.seek(&50), Some(120));
+    assert_eq!(set.seek(&1000), None);
+}
+
+#[test]
+fn ranges_stop_before_their_end() {
+    let mut range = FiniteRange::from(-2i32..3);
+
+    assert_eq!(range.by_ref().collect::<Vec<_>>(), vec![-2, -1, 0, 1, 2]);
+    assert_eq!(range.next(), None);
+    assert!(range.contains(&-2));
+    assert!(!range.contains(&3));
+    assert!(FiniteRange::new(5u8, 5).facts().empty);
+}
+
+#[test]
+fn union_carries_on_after_a_finite_set() {
+    let extras = FiniteSet::new(vec![3u64, 7, 100]);
+    let union = InfiniteEvens::new().union(extras);
+
+    assert_eq!(
+        union.take(8).collect::<Vec<_>>(),
+        vec![2, 3, 4, 6, 7, 8, 10, 12]
+    );
+}
+
+#[test]
+fn intersection_with_a_finite_set_is_finite() {
+    let candidates = FiniteSet::new(vec![4u128, 5, 6, 7, 8, 9, 100]);
+    let primes = InfinitePrimes::new().intersect(candidates);
+
+    assert_eq!(primes.collect::<Vec<_>>(), vec![5, 7]);
+
+    let teens = InfinitePrimes::<u64>::new().intersect(FiniteRange::new(10, 20));
+    assert_eq!(teens.collect::<Vec<_>>(), vec![11, 13, 17, 19]);
+}
+
+#[test]
+fn composed_intersection_with_a_finite_set_is_finite() {
+    let squares = FiniteSet::new((1..=10u32).map(|x| x * x));
+    let odd_squares = Intersection::new(InfiniteOdds::new(), squares);
+
+    assert_eq!(odd_squares.collect::<Vec<_>>(), vec![1, 9, 25, 49, 81]);
+}
+
+#[test]
+fn differences_with_finite_sets() {
+    let outside = InfinitePositiveInts::<u32>::new().difference(FiniteRange::new(1, 10));
+    assert_eq!(outside.take(3).collect::<Vec<_>>(), vec![10, 11, 12]);
+
+    let even = FiniteSet::new(vec![1u32, 2, 3, 4, 5]).difference(InfiniteOdds::new());
+    assert_eq!(even.collect::<Vec<_>>(), vec![2, 4]);
+}
+
+#[test]
+fn bounds_prove_finite_sets_disjoint() {
+    let never = FiniteRange::<u32>::new(1, 10).intersect(FiniteSet::new(vec![10, 20]));
+    assert!(never.is_known_empty());
+
+    let facts = FiniteRange::<u32>::new(1, 10)
+        .union(FiniteSet::new(vec![10, 20]))
+        .facts();
+    assert_eq!(facts.lower_bound, Some(1));
+    assert_eq!(facts.upper_bound, Some(20));
+
+    let unbounded = InfiniteEvens::<u32>::new().union(FiniteRange::new(1, 10));
+    assert_eq!(unbounded.facts().upper_bound, None);
+}
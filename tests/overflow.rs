@@ -0,0 +1,71 @@
+use infinite_sets::prelude::*;
+use infinite_sets::OverflowError;
+
+#[test]
+fn sets_stop_at_the_end_of_their_element_type() {
+    let mut evens = InfiniteEvens::<u8>::new();
+
+    assert_eq!(evens.by_ref().last(), Some(254));
+    assert_eq!(evens.next(), None);
+    assert_eq!(
+        evens.overflow(),
+        Some(OverflowError {
+            set: "InfiniteEvens",
+            element: "u8",
+        })
+    );
+}
+
+#[test]
+fn sets_only_report_overflow_once_they_overflow() {
+    let mut odds = InfiniteOdds::<u8>::new();
+
+    assert_eq!(odds.nth(127), Some(255));
+    assert_eq!(odds.overflow(), None);
+    assert_eq!(odds.next(), None);
+    assert!(odds.overflow().is_some());
+}
+
+#[test]
+#[should_panic(expected = "InfiniteTwoPowers ran past the largest value of u8")]
+fn panic_policy_panics_on_overflow() {
+    let powers = InfiniteTwoPowers::<u8>::new().with_overflow_policy(OverflowPolicy::Panic);
+
+    for _ in powers {}
+}
+
+#[test]
+fn union_outlives_an_overflowed_set() {
+    let mut union = InfiniteTwoPowers::<u8>::new().union(InfiniteOdds::new());
+
+    // the powers of two stop at 128, but the odd numbers carry on to 255
+    assert_eq!(union.by_ref().filter(|x| x % 2 == 0).last(), Some(128));
+    assert_eq!(union.next(), None);
+    assert_eq!(
+        union.overflow().map(|error| error.set),
+        Some("InfiniteTwoPowers")
+    );
+}
+
+#[test]
+fn intersection_ends_with_any_of_its_sets() {
+    let mut odd_primes = InfiniteOdds::<u8>::new().intersect(InfinitePrimes::new());
+
+    // the odd numbers carry on to 255, but the next prime after 251 doesn't fit in a u8
+    assert_eq!(odd_primes.by_ref().last(), Some(251));
+    assert_eq!(odd_primes.try_next_within(10), Ok(None));
+    assert_eq!(
+        odd_primes.overflow().map(|error| error.set),
+        Some("InfinitePrimes")
+    );
+}
+
+#[test]
+fn bidirectional_sets_stop_in_each_direction() {
+    let mut integers = InfiniteIntegers::<i8>::new();
+
+    let lowest = std::iter::from_fn(|| integers.next_down()).last();
+    assert_eq!(lowest, Some(-128));
+    assert!(integers.overflow().is_some());
+    assert_eq!(integers.next_up(), Some(0));
+}
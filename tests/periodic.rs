@@ -0,0 +1,145 @@
+use infinite_sets::prelude::*;
+
+#[test]
+fn periodic_sets_repeat_their_residues() {
+    let set = PeriodicSet::<u128>::new(6, vec![5, 1, 7]);
+
+    assert_eq!(set.residues(), &[1, 5]);
+    assert_eq!(set.density(), 1.0 / 3.0);
+    assert!(set.contains(&11));
+    assert!(!set.contains(&0));
+    assert!(!set.contains(&9));
+    assert_eq!(set.take(5).collect::<Vec<_>>(), vec![1, 5, 7, 11, 13]);
+
+    // the multiples of the period are positive, so they start at the period itself
+    let multiples: Vec<u64> = PeriodicSet::new(4, vec![0]).take(3).collect();
+    assert_eq!(multiples, vec![4, 8, 12]);
+}
+
+#[test]
+fn periodic_sets_use_their_shortest_period() {
+    let odds = PeriodicSet::<u128>::new(12, vec![1, 3, 5, 7, 9, 11]);
+
+    assert_eq!((odds.period(), odds.residues()), (2, &[1][..]));
+}
+
+#[test]
+fn periodic_algebra_is_exact() {
+    let a = PeriodicSet::<u128>::new(4, vec![1, 2]);
+    let b = PeriodicSet::new(6, vec![2, 3, 5]);
+
+    let union = a.union_periodic(&b).unwrap();
+    let intersection = a.intersect_periodic(&b).unwrap();
+    let difference = a.difference_periodic(&b).unwrap();
+    let complement = a.complement().unwrap();
+
+    assert_eq!(union.period(), 12);
+    for x in 0..200 {
+        assert_eq!(union.contains(&x), a.contains(&x) || b.contains(&x));
+        assert_eq!(intersection.contains(&x), a.contains(&x) && b.contains(&x));
+        assert_eq!(difference.contains(&x), a.contains(&x) && !b.contains(&x));
+        assert_eq!(complement.contains(&x), x > 0 && !a.contains(&x));
+    }
+
+    // the complement of the complement is the set again
+    assert_eq!(complement.complement().unwrap().residues(), a.residues());
+}
+
+#[test]
+fn periodic_algebra_refuses_sets_too_large_to_build() {
+    // the lcm of these periods doesn't fit in a u128
+    let a = PeriodicSet::<u128>::new(1 << 127, vec![1]);
+    let b = PeriodicSet::new(3, vec![1]);
+    assert!(a.union_periodic(&b).is_none());
+    assert!(a.intersect_periodic(&b).is_none());
+    assert!(a.difference_periodic(&b).is_none());
+
+    // these fit, but lifted to the lcm they would have about 200,000 residues between them
+    let c = PeriodicSet::<u128>::new(1009, (0..100).collect::<Vec<_>>());
+    let d = PeriodicSet::new(1013, (0..100).collect::<Vec<_>>());
+    assert!(c.union_periodic(&d).is_none());
+    assert!(c.difference_periodic(&d).is_none());
+    assert!(c.intersect_periodic(&d).is_some());
+    assert!(c.complement().is_some());
+    assert!(PeriodicSet::<u128>::new(1 << 100, vec![1])
+        .complement()
+        .is_none());
+}
+
+#[test]
+fn unions_of_periodic_sets_collapse() {
+    let union = ArithmeticProgression::<u128>::new(3, 6)
+        .union(ArithmeticProgression::new(1, 4))
+        .union(InfiniteEvens::new());
+
+    let collapsed = union.as_periodic().unwrap();
+    assert_eq!(
+        (collapsed.period(), collapsed.residues()),
+        (12, &[0, 1, 2, 3, 4, 5, 6, 8, 9, 10][..])
+    );
+
+    let expected: Vec<u128> = (1..100)
+        .filter(|x| x % 6 == 3 || x % 4 == 1 || x % 2 == 0)
+        .collect();
+    assert_eq!(union.take(expected.len()).collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn intersections_and_differences_of_periodic_sets_collapse() {
+    let intersection =
+        ArithmeticProgression::<u128>::new(1, 4).intersect(PeriodicSet::new(6, vec![3]));
+    let collapsed = intersection.as_periodic().unwrap();
+    assert_eq!((collapsed.period(), collapsed.residues()), (12, &[9][..]));
+    assert_eq!(intersection.take(3).collect::<Vec<_>>(), vec![9, 21, 33]);
+
+    let odds = InfinitePositiveInts::<u128>::new().difference(InfiniteEvens::new());
+    assert_eq!(odds.take(4).collect::<Vec<_>>(), vec![1, 3, 5, 7]);
+}
+
+#[test]
+fn collapsed_intersections_can_be_known_empty() {
+    // the residues of these sets don't share a residue class, but no residue is in both
+    let intersection =
+        PeriodicSet::<u128>::new(6, vec![1, 2]).intersect(PeriodicSet::new(6, vec![3, 4]));
+
+    assert!(intersection.is_known_empty());
+}
+
+#[test]
+fn partly_iterated_sets_keep_their_place() {
+    let mut evens = InfiniteEvens::<u128>::new();
+    evens.nth(1);
+
+    // the evens have already yielded 2 and 4, so the union can't start over from 1
+    let union: Vec<u128> = evens.union(InfiniteOdds::new()).take(6).collect();
+    assert_eq!(union, vec![1, 3, 5, 6, 7, 8]);
+
+    let mut odds = InfiniteOdds::<u128>::new();
+    odds.next();
+    let intersection: Vec<u128> = odds
+        .intersect(ArithmeticProgression::new(3, 3))
+        .take(3)
+        .collect();
+    assert_eq!(intersection, vec![3, 9, 15]);
+}
+
+#[test]
+fn collapsed_sets_stop_at_the_end_of_their_element_type() {
+    let mut union = InfiniteEvens::<u8>::new().union(InfiniteOdds::new());
+
+    assert_eq!(union.by_ref().count(), 255);
+    assert_eq!(
+        union.overflow().map(|error| error.set),
+        Some("InfiniteEvens")
+    );
+}
+
+#[test]
+#[should_panic(expected = "InfiniteOdds ran past the largest value of u8")]
+fn sets_with_different_overflow_policies_dont_collapse() {
+    let odds = InfiniteOdds::<u8>::new().with_overflow_policy(OverflowPolicy::Panic);
+    let union = InfiniteEvens::<u8>::new().union(odds);
+
+    assert!(union.as_periodic().is_none());
+    union.for_each(drop);
+}
@@ -0,0 +1,68 @@
+use infinite_sets::prelude::*;
+
+fn digit_sum(mut x: u64) -> u64 {
+    let mut sum = 0;
+    while x > 0 {
+        sum += x % 10;
+        x /= 10;
+    }
+
+    sum
+}
+
+#[test]
+fn predicate_sets_walk_their_universe() {
+    let sevens = InfiniteSet::from_predicate(InfinitePositiveInts::new(), |x| digit_sum(*x) == 7);
+
+    assert_eq!(
+        sevens.take(9).collect::<Vec<u64>>(),
+        vec![7, 16, 25, 34, 43, 52, 61, 70, 106]
+    );
+}
+
+#[test]
+fn predicate_sets_contain_what_both_the_universe_and_predicate_do() {
+    let sevens = InfiniteSet::from_predicate(InfiniteOdds::new(), |x| digit_sum(*x) == 7);
+
+    assert!(sevens.contains(&1_000_033));
+    assert!(!sevens.contains(&70));
+    assert!(!sevens.contains(&17));
+}
+
+#[test]
+fn predicate_sets_seek_through_their_universe() {
+    let mut sevens = InfiniteSet::from_predicate(InfiniteOdds::new(), |x| digit_sum(*x) == 7);
+
+    assert_eq!(sevens.seek(&1_000_000), Some(1_000_015));
+    assert_eq!(sevens.next(), Some(1_000_033));
+}
+
+#[test]
+fn predicate_sets_combine_with_other_sets() {
+    let sevens = InfiniteSet::from_predicate(InfinitePositiveInts::new(), |x| digit_sum(*x) == 7);
+    let prime_sevens = InfinitePrimes::new().intersect(sevens);
+
+    assert_eq!(
+        prime_sevens.take(5).collect::<Vec<u64>>(),
+        vec![7, 43, 61, 151, 223]
+    );
+}
+
+#[test]
+fn predicate_sets_borrow_what_their_predicate_uses() {
+    let banned = [4u64, 13];
+    let allowed = InfiniteSet::from_predicate(InfinitePositiveInts::new(), |x| !banned.contains(x));
+    let lucky = Union::new(allowed, InfiniteEvens::new());
+
+    assert!(!lucky.contains(&13));
+    assert_eq!(lucky.take(5).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn budget_ends_a_predicate_set_instead_of_stalling() {
+    let mut small = InfiniteSet::from_predicate(InfinitePositiveInts::<u32>::new(), |x| *x < 3)
+        .with_budget(100);
+
+    assert_eq!(small.by_ref().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(small.try_next_within(10).is_err());
+}
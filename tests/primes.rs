@@ -0,0 +1,56 @@
+use infinite_sets::prelude::*;
+
+#[test]
+fn primes_keep_coming_past_the_first_segments() {
+    let primes: Vec<u128> = InfinitePrimes::new().take(100_000).collect();
+
+    assert_eq!(primes[9_999], 104_729);
+    assert_eq!(primes[99_999], 1_299_709);
+    assert!(primes.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn contains_uses_the_whole_u128_value() {
+    let primes = InfinitePrimes::<u128>::new();
+
+    // the first prime past 2^64, and the last prime before 2^128
+    assert!(primes.contains(&((1 << 64) + 13)));
+    assert!(primes.contains(&(u128::MAX - 158)));
+    // 2^127 - 1 is a Mersenne prime
+    assert!(primes.contains(&((1 << 127) - 1)));
+
+    // 3 * (2^64 + 1) is composite, even though it would truncate to 3 in a u64
+    assert!(!primes.contains(&(3 * ((1 << 64) + 1))));
+    // a product of two 64-bit primes
+    assert!(!primes.contains(&165_488_441_585_315_698_004_502_434_776_012_062_181));
+    assert!(!primes.contains(&u128::MAX));
+}
+
+#[test]
+fn contains_rejects_strong_pseudoprimes() {
+    let primes = InfinitePrimes::<u128>::new();
+
+    // strong pseudoprimes to the bases 2, 3, 5 and 7, and to every base up to 37
+    assert!(!primes.contains(&3_215_031_751));
+    assert!(!primes.contains(&318_665_857_834_031_151_167_461));
+    // Carmichael numbers
+    assert!(!primes.contains(&561));
+    assert!(!primes.contains(&1105));
+}
+
+#[test]
+fn negative_numbers_are_not_prime() {
+    assert!(!InfinitePrimes::<i64>::new().contains(&-7));
+}
+
+#[test]
+fn primes_end_at_the_last_prime_before_u128_max() {
+    let mut primes = InfinitePrimes::<u128>::new();
+
+    assert_eq!(primes.seek(&(u128::MAX - 170)), Some(u128::MAX - 158));
+    assert_eq!(primes.next(), None);
+    assert_eq!(
+        primes.overflow().map(|error| error.set),
+        Some("InfinitePrimes")
+    );
+}
@@ -0,0 +1,100 @@
+use infinite_sets::prelude::*;
+
+/// Szudzik's pairing function, which numbers the pairs of natural numbers in order.
+fn szudzik(a: u64, b: u64) -> u64 {
+    if a < b {
+        b * b + a
+    } else {
+        a * a + a + b
+    }
+}
+
+#[test]
+fn pairs_are_ordered_like_szudzik_pairing() {
+    let mut by_order: Vec<Pair<u64>> = (0..20)
+        .flat_map(|a| (0..20).map(move |b| Pair(a, b)))
+        .collect();
+    let mut by_number = by_order.clone();
+    by_order.sort();
+    by_number.sort_by_key(|pair| szudzik(pair.0, pair.1));
+
+    assert_eq!(by_order, by_number);
+}
+
+#[test]
+fn products_reach_every_pair_in_order() {
+    let pairs: Vec<(u64, u64)> =
+        InfiniteProduct::new(InfinitePositiveInts::new(), InfinitePositiveInts::new())
+            .take(9)
+            .map(Into::into)
+            .collect();
+
+    assert_eq!(
+        pairs,
+        vec![
+            (1, 1),
+            (1, 2),
+            (2, 1),
+            (2, 2),
+            (1, 3),
+            (2, 3),
+            (3, 1),
+            (3, 2),
+            (3, 3)
+        ]
+    );
+}
+
+#[test]
+fn products_of_sparse_sets_agree_with_sorting_every_pair() {
+    let primes: Vec<u64> = InfinitePrimes::new().take_while(|p| *p < 50).collect();
+    let odds: Vec<u64> = InfiniteOdds::new().take_while(|x| *x < 50).collect();
+    let mut expected: Vec<Pair<u64>> = primes
+        .iter()
+        .flat_map(|p| odds.iter().map(move |x| Pair(*p, *x)))
+        .collect();
+    expected.sort();
+
+    let product = InfiniteProduct::new(InfinitePrimes::new(), InfiniteOdds::new());
+    let pairs: Vec<Pair<u64>> = product.take(expected.len()).collect();
+    assert_eq!(pairs, expected);
+}
+
+#[test]
+fn products_contain_pairs_of_their_sets() {
+    let product = InfiniteProduct::new(InfinitePrimes::<u64>::new(), InfiniteOdds::new());
+
+    assert!(product.contains(&Pair(7, 9)));
+    assert!(!product.contains(&Pair(9, 7)));
+    assert!(!product.contains(&Pair(7, 8)));
+    assert_eq!(product.facts().lower_bound, Some(Pair(2, 1)));
+}
+
+#[test]
+fn products_combine_with_union() {
+    let prime_or_even =
+        InfiniteProduct::new(InfinitePrimes::<u64>::new(), InfiniteOdds::new()).union(
+            InfiniteProduct::new(InfiniteEvens::new(), InfiniteOdds::new()),
+        );
+    let expected = InfiniteProduct::new(
+        InfinitePrimes::new().union(InfiniteEvens::new()),
+        InfiniteOdds::new(),
+    );
+
+    assert_eq!(
+        prime_or_even.take(100).collect::<Vec<_>>(),
+        expected.take(100).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn products_of_finite_sets_end() {
+    let pairs: Vec<Pair<u32>> =
+        InfiniteProduct::new(FiniteSet::new(vec![1, 5]), FiniteSet::new(vec![2, 3])).collect();
+    assert_eq!(pairs.len(), 4);
+    assert!(pairs.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let mut never = InfiniteProduct::new(InfiniteOdds::<u32>::new(), EmptySet::new());
+    assert_eq!(never.next(), None);
+    assert!(never.facts().empty);
+}
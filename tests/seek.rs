@@ -0,0 +1,63 @@
+use infinite_sets::prelude::*;
+
+/// Seeks `set` to each of the bounds in turn, along with a fresh copy of the set that walks
+/// there one element at a time, and checks that they agree.
+fn assert_seeks_like_walking<S: InfiniteSet<Item = u128>>(mut make: impl FnMut() -> S) {
+    let mut set = make();
+    let mut walked = make();
+
+    for bound in [0, 1, 2, 7, 7, 30, 31, 100, 50, 1000] {
+        let expected = loop {
+            match walked.next() {
+                Some(x) if x < bound => continue,
+                x => break x,
+            }
+        };
+
+        assert_eq!(set.seek(&bound), expected, "seeking {}", bound);
+    }
+}
+
+#[test]
+fn built_in_sets_seek_like_walking() {
+    assert_seeks_like_walking(InfinitePositiveInts::new);
+    assert_seeks_like_walking(InfinitePrimes::new);
+    assert_seeks_like_walking(InfiniteEvens::new);
+    assert_seeks_like_walking(InfiniteOdds::new);
+    assert_seeks_like_walking(InfiniteTwoPowers::new);
+    assert_seeks_like_walking(|| InfinitePowers::new(3));
+    assert_seeks_like_walking(|| ArithmeticProgression::new(4, 7));
+    assert_seeks_like_walking(|| PeriodicSet::new(10, vec![3, 4, 9]));
+    assert_seeks_like_walking(InfiniteFactorials::new);
+}
+
+#[test]
+fn combinators_seek_like_walking() {
+    assert_seeks_like_walking(|| InfiniteTwoPowers::new().union(InfiniteOdds::new()));
+    assert_seeks_like_walking(|| InfiniteOdds::new().intersect(InfinitePrimes::new()));
+    assert_seeks_like_walking(|| InfiniteOdds::new().difference(InfinitePrimes::new()));
+    assert_seeks_like_walking(|| InfinitePrimes::new().symmetric_difference(InfiniteOdds::new()));
+}
+
+#[test]
+fn seeking_jumps_far_ahead() {
+    let mut primes = InfinitePrimes::<u128>::new();
+    assert_eq!(primes.seek(&1_000_000_000_000), Some(1_000_000_000_039));
+    assert_eq!(primes.next(), Some(1_000_000_000_061));
+
+    let mut evens = InfiniteEvens::<u64>::new();
+    assert_eq!(evens.seek(&(u64::MAX - 2)), Some(u64::MAX - 1));
+    assert_eq!(evens.seek(&u64::MAX), None);
+    assert!(evens.overflow().is_some());
+}
+
+#[test]
+fn intersection_leaps_over_a_dense_set() {
+    // walking the positive ints up to 2^100 would never finish, but every seek is one step
+    let powers: Vec<u128> = InfinitePositiveInts::new()
+        .intersect(InfiniteTwoPowers::new())
+        .take(101)
+        .collect();
+
+    assert_eq!(powers.last(), Some(&(1 << 100)));
+}
@@ -0,0 +1,263 @@
+use infinite_sets::prelude::*;
+use infinite_sets::OverflowError;
+
+#[test]
+fn positive_ints_start_at_one() {
+    let ints: Vec<u128> = InfinitePositiveInts::new().take(5).collect();
+
+    assert_eq!(ints, vec![1, 2, 3, 4, 5]);
+    assert!(!InfinitePositiveInts::new().contains(&0));
+}
+
+#[test]
+fn evens_and_odds_partition_the_positive_ints() {
+    let evens = InfiniteEvens::new();
+    let odds = InfiniteOdds::new();
+
+    for x in 1..100 {
+        assert_ne!(evens.contains(&x), odds.contains(&x));
+    }
+    assert!(!evens.contains(&0));
+    assert!(!odds.contains(&0));
+}
+
+#[test]
+fn primes_are_listed_in_order() {
+    let primes: Vec<u128> = InfinitePrimes::new().take(8).collect();
+
+    assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19]);
+    assert!(InfinitePrimes::new().contains(&97));
+    assert!(!InfinitePrimes::new().contains(&91));
+}
+
+#[test]
+fn two_powers_are_listed_in_order() {
+    let powers: Vec<u128> = InfiniteTwoPowers::new().take(6).collect();
+
+    assert_eq!(powers, vec![1, 2, 4, 8, 16, 32]);
+}
+
+#[test]
+fn sets_can_be_made_of_other_integer_types() {
+    let evens: Vec<u32> = InfiniteEvens::new().take(3).collect();
+    let odds: Vec<i64> = InfiniteOdds::new().take(3).collect();
+    let primes: Vec<u64> = InfinitePrimes::new().take(3).collect();
+
+    assert_eq!(evens, vec![2, 4, 6]);
+    assert_eq!(odds, vec![1, 3, 5]);
+    assert_eq!(primes, vec![2, 3, 5]);
+    assert!(!InfiniteOdds::<i64>::new().contains(&-3));
+}
+
+#[test]
+fn small_element_types_end_the_primes_early() {
+    let primes: Vec<u8> = InfinitePrimes::new().collect();
+
+    assert_eq!(primes.len(), 54);
+    assert_eq!(primes.last(), Some(&251));
+}
+
+#[test]
+fn factorials_are_listed_in_order() {
+    let factorials: Vec<u128> = InfiniteFactorials::new().take(6).collect();
+    assert_eq!(factorials, vec![1, 2, 6, 24, 120, 720]);
+
+    let set = InfiniteFactorials::<u128>::new();
+    assert!(set.contains(&5040));
+    assert!(!set.contains(&12));
+    assert!(!set.contains(&0));
+}
+
+#[test]
+fn fibonacci_numbers_are_listed_once_each() {
+    let fibonacci: Vec<u128> = InfiniteFibonacci::new().take(8).collect();
+    assert_eq!(fibonacci, vec![1, 2, 3, 5, 8, 13, 21, 34]);
+
+    let set = InfiniteFibonacci::<u128>::new();
+    assert!(set.contains(&1));
+    assert!(set.contains(&144));
+    assert!(!set.contains(&0));
+    assert!(!set.contains(&4));
+}
+
+#[test]
+fn fibonacci_contains_is_exact_across_the_element_type() {
+    let set = InfiniteFibonacci::<u128>::new();
+
+    // past 2^61 or so, 5x^2 + 4 no longer fits in a u128
+    for x in InfiniteFibonacci::<u128>::new().skip(3) {
+        assert!(set.contains(&x));
+        assert!(!set.contains(&(x + 1)));
+    }
+    assert_eq!(InfiniteFibonacci::<u128>::new().count(), 185);
+}
+
+#[test]
+fn linear_recurrences() {
+    // the Pell numbers, a(n) = 2a(n - 1) + a(n - 2)
+    let pell = LinearRecurrence::<u128>::new(vec![2, 1], vec![1, 2]);
+    assert!(pell.contains(&70));
+    assert!(!pell.contains(&71));
+    assert_eq!(pell.take(6).collect::<Vec<_>>(), vec![1, 2, 5, 12, 29, 70]);
+
+    let tribonacci = LinearRecurrence::<u64>::new(vec![1, 1, 1], vec![1, 1, 2]);
+    assert_eq!(
+        tribonacci.take(6).collect::<Vec<_>>(),
+        vec![1, 2, 4, 7, 13, 24]
+    );
+}
+
+#[test]
+#[should_panic(expected = "must keep it growing")]
+fn recurrences_have_to_grow() {
+    // a(n) = a(n - 2) would go 1, 2, 1, 2, ...
+    LinearRecurrence::<u32>::new(vec![0, 1], vec![1, 2]);
+}
+
+#[test]
+fn two_powers_contain_exactly_the_powers_of_two() {
+    let powers = InfiniteTwoPowers::<u128>::new();
+
+    assert!(powers.contains(&1));
+    assert!(powers.contains(&64));
+    assert!(powers.contains(&(1 << 127)));
+    assert!(!powers.contains(&0));
+    assert!(!powers.contains(&96));
+    // f64 can't tell these apart from 2^127
+    assert!(!powers.contains(&((1 << 127) + 1)));
+    assert!(!powers.contains(&((1 << 127) - 1)));
+}
+
+#[test]
+fn powers_of_any_base() {
+    let threes: Vec<u64> = InfinitePowers::new(3).take(5).collect();
+    assert_eq!(threes, vec![1, 3, 9, 27, 81]);
+
+    let tens = InfinitePowers::<u128>::new(10);
+    assert!(tens.contains(&10u128.pow(38)));
+    assert!(!tens.contains(&(10u128.pow(38) + 1)));
+    assert!(!tens.contains(&20));
+
+    // 3^40 is the largest power of three in a u64
+    assert_eq!(InfinitePowers::<u64>::new(3).last(), Some(3u64.pow(40)));
+}
+
+#[test]
+#[should_panic(expected = "must be at least 2")]
+fn powers_need_a_base_of_at_least_two() {
+    InfinitePowers::<u32>::new(1);
+}
+
+#[test]
+fn arithmetic_progressions() {
+    let progression = ArithmeticProgression::<u128>::new(5, 3);
+
+    assert_eq!(progression.start(), &5);
+    assert_eq!(progression.step(), &3);
+    assert!(progression.contains(&5));
+    assert!(progression.contains(&(5 + 3 * 10u128.pow(30))));
+    assert!(!progression.contains(&2));
+    assert!(!progression.contains(&6));
+
+    let terms: Vec<u128> = progression.take(4).collect();
+    assert_eq!(terms, vec![5, 8, 11, 14]);
+
+    let below_zero: Vec<i32> = ArithmeticProgression::new(-7, 4).take(4).collect();
+    assert_eq!(below_zero, vec![-7, -3, 1, 5]);
+}
+
+#[test]
+fn progressions_intersect_in_closed_form() {
+    // x = 1 (mod 4) and x = 3 (mod 6) meet at 9 (mod 12)
+    let first = ArithmeticProgression::<u128>::new(1, 4);
+    let second = ArithmeticProgression::new(3, 6);
+    let both = first.intersect_progression(&second).unwrap().unwrap();
+
+    assert_eq!((both.start(), both.step()), (&9, &12));
+    for x in 0..1000 {
+        assert_eq!(both.contains(&x), first.contains(&x) && second.contains(&x));
+    }
+
+    // a later start pushes the first common element up
+    let late = ArithmeticProgression::new(51, 6);
+    let both = first.intersect_progression(&late).unwrap().unwrap();
+    assert_eq!(both.take(2).collect::<Vec<_>>(), vec![57, 69]);
+
+    // starts can be negative, and the later one decides where the intersection starts
+    let negative = ArithmeticProgression::<i64>::new(-10, 15);
+    let both = negative
+        .intersect_progression(&ArithmeticProgression::new(-3, 7))
+        .unwrap()
+        .unwrap();
+    assert_eq!((both.start(), both.step()), (&95, &105));
+}
+
+#[test]
+fn disjoint_progressions_have_no_intersection() {
+    let odds = ArithmeticProgression::<u128>::new(1, 2);
+    let twos = ArithmeticProgression::new(4, 6);
+
+    assert_eq!(
+        odds.intersect_progression(&twos).map(|both| both.is_none()),
+        Ok(true)
+    );
+    assert!(odds.intersect(twos).is_known_empty());
+
+    // neither of these starts at its first positive term, so the facts can't tell
+    let late_odds = ArithmeticProgression::<i64>::new(101, 2);
+    let late_twos = ArithmeticProgression::new(-20, 6);
+    assert!(late_odds.intersect(late_twos).is_known_empty());
+}
+
+#[test]
+fn intersect_finds_common_elements_of_progressions_in_closed_form() {
+    // x = 1 (mod 3) and x = 4 (mod 7) meet at 4 (mod 21), first at 214
+    let mut both =
+        ArithmeticProgression::<u64>::new(100, 3).intersect(ArithmeticProgression::new(200, 7));
+    assert_eq!(both.try_next_within(1), Ok(Some(214)));
+    assert_eq!(both.take(2).collect::<Vec<_>>(), vec![235, 256]);
+
+    // steps this large would take about 10^12 leaps to search through
+    let first = ArithmeticProgression::<u128>::new(1, 999_999_999_989);
+    let second = ArithmeticProgression::new(2, 999_999_999_961);
+    let mut both = first.intersect(second);
+    let x = both.try_next_within(1).unwrap().unwrap();
+    assert_eq!(
+        ((x - 1) % 999_999_999_989, (x - 2) % 999_999_999_961),
+        (0, 0)
+    );
+
+    // partly iterated progressions only share what they have left
+    let mut threes = ArithmeticProgression::<u64>::new(3, 3);
+    threes.nth(9);
+    let both = threes.intersect(ArithmeticProgression::new(5, 10));
+    assert_eq!(both.take(2).collect::<Vec<_>>(), vec![45, 75]);
+}
+
+#[test]
+fn progressions_whose_steps_have_no_lcm_in_the_element_type() {
+    let first = ArithmeticProgression::<u128>::new(1, 1 << 100);
+    let second = ArithmeticProgression::new(1, (1 << 100) - 1);
+
+    assert_eq!(
+        first.intersect_progression(&second).err(),
+        Some(OverflowError {
+            set: "ArithmeticProgression",
+            element: "u128",
+        })
+    );
+
+    // these meet at 51 (mod 255), so their first common element would be 306
+    let high = ArithmeticProgression::<u8>::new(251, 5);
+    let low = ArithmeticProgression::new(0, 51);
+    assert!(high.intersect_progression(&low).is_err());
+}
+
+#[test]
+fn signed_progressions_contain_values_far_from_their_start() {
+    let odds = ArithmeticProgression::<i64>::new(-1, 2);
+
+    assert!(odds.contains(&i64::MAX));
+    assert!(!odds.contains(&(i64::MAX - 1)));
+    assert!(!odds.contains(&-3));
+}